@@ -0,0 +1,291 @@
+use regex::Regex;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+const DEFAULT_JSON_PRESET: &str = "tuna";
+const DEFAULT_LOGFMT_SOURCE: &str = "service";
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub json: JsonMappingConfig,
+    pub logfmt: LogfmtConfig,
+    pub container: ContainerConfig,
+    pub csv: CsvMappingConfig,
+    pub continuation: ContinuationConfig,
+    pub timestamp_formats: Vec<String>,
+    pub formats: Vec<RegexFormatConfig>,
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct JsonMappingConfig {
+    pub preset: Option<String>,
+    pub message: Option<String>,
+    pub timestamp: Option<String>,
+    pub source: Option<String>,
+    pub level: Option<String>,
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LogfmtConfig {
+    pub source: Option<String>,
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ContainerConfig {
+    pub decode_json: bool,
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct CsvMappingConfig {
+    pub message: Option<String>,
+    pub timestamp: Option<String>,
+    pub source: Option<String>,
+    pub level: Option<String>,
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ContinuationConfig {
+    pub mode: Option<ContinuationMode>,
+    pub new_entry_pattern: Option<String>,
+    pub max_lines: Option<usize>,
+}
+
+/// How lines which no parser accepts are attached to the previous entry.
+#[derive(Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ContinuationMode {
+    /// Every line continues the previous entry.
+    #[default]
+    Any,
+    /// Only indented and blank lines continue the previous entry.
+    Indented,
+    /// Every line becomes an unparsed entry of its own.
+    Standalone,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct RegexFormatConfig {
+    pub name: String,
+    pub pattern: String,
+    #[serde(default)]
+    pub date_format: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct JsonMapping {
+    pub message: String,
+    pub timestamp: String,
+    pub source: String,
+    pub level: String,
+}
+
+impl Config {
+    pub fn load(path: Option<&str>) -> Result<Self, String> {
+        let path = match path {
+            Some(path) => PathBuf::from(path),
+            None => match Config::default_path().filter(|path| path.exists()) {
+                Some(path) => path,
+                None => return Ok(Config::default()),
+            },
+        };
+        let content = std::fs::read_to_string(&path)
+            .map_err(|error| format!("Failed to read config {path:?}: {error}"))?;
+        let config = serde_json::from_str::<Config>(&content)
+            .map_err(|error| format!("Invalid config {path:?}: {error}"))?;
+        config.json.validate()?;
+        config.continuation.compile()?;
+        for format in &config.formats {
+            format.validate()?;
+        }
+        Ok(config)
+    }
+
+    fn default_path() -> Option<PathBuf> {
+        let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(config_dir.join("tuna").join("config.json"))
+    }
+}
+
+impl JsonMappingConfig {
+    pub fn override_with(&mut self, other: &JsonMappingConfig) {
+        let fields = [
+            (&mut self.preset, &other.preset),
+            (&mut self.message, &other.message),
+            (&mut self.timestamp, &other.timestamp),
+            (&mut self.source, &other.source),
+            (&mut self.level, &other.level),
+        ];
+        for (field, value) in fields {
+            if value.is_some() {
+                *field = value.clone();
+            }
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self.preset.as_deref() {
+            Some(preset) if JsonMapping::preset(preset).is_none() => Err(format!(
+                "Unknown JSON preset: {preset}. Available presets: {}",
+                JsonMapping::PRESETS.join(", ")
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn mapping(&self) -> JsonMapping {
+        let preset = self.preset.as_deref().unwrap_or(DEFAULT_JSON_PRESET);
+        let mut mapping = JsonMapping::preset(preset)
+            .unwrap_or_else(|| JsonMapping::preset(DEFAULT_JSON_PRESET).unwrap());
+        let fields = [
+            (&mut mapping.message, &self.message),
+            (&mut mapping.timestamp, &self.timestamp),
+            (&mut mapping.source, &self.source),
+            (&mut mapping.level, &self.level),
+        ];
+        for (field, value) in fields {
+            if let Some(value) = value {
+                *field = value.clone();
+            }
+        }
+        mapping
+    }
+}
+
+impl LogfmtConfig {
+    pub fn override_with(&mut self, other: &LogfmtConfig) {
+        if other.source.is_some() {
+            self.source = other.source.clone();
+        }
+    }
+
+    pub fn source_key(&self) -> String {
+        self.source
+            .clone()
+            .unwrap_or_else(|| DEFAULT_LOGFMT_SOURCE.to_string())
+    }
+}
+
+impl ContainerConfig {
+    pub fn override_with(&mut self, other: &ContainerConfig) {
+        self.decode_json |= other.decode_json;
+    }
+}
+
+impl CsvMappingConfig {
+    pub fn override_with(&mut self, other: &CsvMappingConfig) {
+        let fields = [
+            (&mut self.message, &other.message),
+            (&mut self.timestamp, &other.timestamp),
+            (&mut self.source, &other.source),
+            (&mut self.level, &other.level),
+        ];
+        for (field, value) in fields {
+            if value.is_some() {
+                *field = value.clone();
+            }
+        }
+    }
+}
+
+impl ContinuationConfig {
+    pub fn override_with(&mut self, other: &ContinuationConfig) {
+        if other.mode.is_some() {
+            self.mode = other.mode;
+        }
+        if other.new_entry_pattern.is_some() {
+            self.new_entry_pattern = other.new_entry_pattern.clone();
+        }
+        if other.max_lines.is_some() {
+            self.max_lines = other.max_lines;
+        }
+    }
+
+    pub fn compile(&self) -> Result<Option<Regex>, String> {
+        self.new_entry_pattern
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .map_err(|error| format!("Invalid new entry pattern: {error}"))
+    }
+}
+
+impl ContinuationMode {
+    pub const NAMES: [&'static str; 3] = ["any", "indented", "standalone"];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "any" => Some(ContinuationMode::Any),
+            "indented" => Some(ContinuationMode::Indented),
+            "standalone" => Some(ContinuationMode::Standalone),
+            _ => None,
+        }
+    }
+}
+
+impl RegexFormatConfig {
+    const REQUIRED_GROUPS: [&'static str; 2] = ["date", "message"];
+
+    pub fn compile(&self) -> Result<Regex, String> {
+        Regex::new(&self.pattern)
+            .map_err(|error| format!("Invalid pattern of {} format: {error}", self.name))
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let regex = self.compile()?;
+        let missing_group = RegexFormatConfig::REQUIRED_GROUPS
+            .into_iter()
+            .find(|group| !regex.capture_names().flatten().any(|name| name == *group));
+        match missing_group {
+            Some(group) => Err(format!(
+                "Pattern of {} format has no `{group}` group",
+                self.name
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+impl JsonMapping {
+    pub const PRESETS: [&'static str; 9] = [
+        "tuna",
+        "bunyan",
+        "pino",
+        "zap",
+        "structlog",
+        "serilog",
+        "logstash",
+        "ecs",
+        "gelf",
+    ];
+
+    pub fn preset(name: &str) -> Option<Self> {
+        let (message, timestamp, source, level) = match name {
+            "tuna" => ("message", "date", "source", "level"),
+            "bunyan" => ("msg", "time", "name", "level"),
+            "pino" => ("msg", "time", "name", "level"),
+            "zap" => ("msg", "ts", "logger", "level"),
+            "structlog" => ("event", "timestamp", "logger", "level"),
+            "serilog" => ("@m", "@t", "SourceContext", "@l"),
+            "logstash" => ("message", "@timestamp", "logger_name", "level"),
+            "ecs" => ("message", "@timestamp", "service.name", "log.level"),
+            "gelf" => ("short_message", "timestamp", "host", "level"),
+            _ => return None,
+        };
+        Some(Self {
+            message: message.into(),
+            timestamp: timestamp.into(),
+            source: source.into(),
+            level: level.into(),
+        })
+    }
+}
@@ -0,0 +1,122 @@
+use super::log_entry::LogEntry;
+use crate::config::{ContinuationConfig, ContinuationMode};
+use regex::Regex;
+
+/// Decides whether a line no parser accepts is appended to the previous entry
+/// or starts an unparsed entry.
+#[derive(Clone)]
+pub struct ContinuationPolicy {
+    mode: ContinuationMode,
+    new_entry: Option<Regex>,
+    max_lines: Option<usize>,
+}
+
+impl ContinuationPolicy {
+    pub fn new(config: &ContinuationConfig) -> Result<Self, String> {
+        Ok(Self {
+            mode: config.mode.unwrap_or_default(),
+            new_entry: config.compile()?,
+            max_lines: config.max_lines,
+        })
+    }
+
+    /// Returns why `line` can't continue `entry`, or `None` when it can.
+    pub fn rejection(&self, entry: &LogEntry, line: &str) -> Option<&'static str> {
+        let lines_count = entry.lines_count + entry.stack_trace.len();
+        if self
+            .max_lines
+            .is_some_and(|max_lines| lines_count >= max_lines)
+        {
+            return Some("entry reached the maximum lines");
+        }
+        if self
+            .new_entry
+            .as_ref()
+            .is_some_and(|regex| regex.is_match(line))
+        {
+            return Some("matches the new entry pattern");
+        }
+        match self.mode {
+            ContinuationMode::Any => None,
+            ContinuationMode::Indented
+                if line.trim().is_empty() || line.starts_with(char::is_whitespace) =>
+            {
+                None
+            }
+            ContinuationMode::Indented => Some("not indented"),
+            ContinuationMode::Standalone => Some("continuation lines are disabled"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(config: ContinuationConfig) -> ContinuationPolicy {
+        ContinuationPolicy::new(&config).unwrap()
+    }
+
+    #[test]
+    fn accepts_any_line_by_default() {
+        let policy = policy(ContinuationConfig::default());
+        assert_eq!(policy.rejection(&LogEntry::plain("first"), "second"), None);
+    }
+
+    #[test]
+    fn accepts_indented_and_blank_lines() {
+        let policy = policy(ContinuationConfig {
+            mode: Some(ContinuationMode::Indented),
+            ..Default::default()
+        });
+        let entry = LogEntry::plain("first");
+        assert_eq!(policy.rejection(&entry, "  second"), None);
+        assert_eq!(policy.rejection(&entry, ""), None);
+        assert_eq!(policy.rejection(&entry, "second"), Some("not indented"));
+    }
+
+    #[test]
+    fn rejects_every_line_when_standalone() {
+        let policy = policy(ContinuationConfig {
+            mode: Some(ContinuationMode::Standalone),
+            ..Default::default()
+        });
+        let rejection = policy.rejection(&LogEntry::plain("first"), "  second");
+        assert_eq!(rejection, Some("continuation lines are disabled"));
+    }
+
+    #[test]
+    fn rejects_lines_matching_new_entry_pattern() {
+        let policy = policy(ContinuationConfig {
+            new_entry_pattern: Some(r"^\d{4}-".to_string()),
+            ..Default::default()
+        });
+        let entry = LogEntry::plain("first");
+        assert_eq!(policy.rejection(&entry, "  second"), None);
+        let rejection = policy.rejection(&entry, "2023-04-01 started");
+        assert_eq!(rejection, Some("matches the new entry pattern"));
+    }
+
+    #[test]
+    fn counts_stack_trace_lines_towards_maximum() {
+        let policy = policy(ContinuationConfig {
+            max_lines: Some(3),
+            ..Default::default()
+        });
+        let mut entry = LogEntry::plain("first");
+        entry.append("java.lang.IllegalStateException: closed");
+        assert_eq!(policy.rejection(&entry, "\tat Client.send"), None);
+        entry.push_stack_trace("\tat Client.send");
+        let rejection = policy.rejection(&entry, "\tat Client.run");
+        assert_eq!(rejection, Some("entry reached the maximum lines"));
+    }
+
+    #[test]
+    fn fails_on_invalid_pattern() {
+        let config = ContinuationConfig {
+            new_entry_pattern: Some("(".to_string()),
+            ..Default::default()
+        };
+        assert!(ContinuationPolicy::new(&config).is_err());
+    }
+}
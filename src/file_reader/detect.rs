@@ -0,0 +1,195 @@
+use super::parser::{LogParser, ParserRegistry};
+use std::io::{self, BufRead, BufReader, Read};
+
+const SAMPLE_LINES_COUNT: usize = 64;
+/// A format has to recognize the majority of the sample, otherwise plain text is used.
+const MIN_SCORE: f32 = 0.5;
+
+pub struct Sample {
+    pub bytes: Vec<u8>,
+}
+
+pub struct Detection {
+    pub parser: Box<dyn LogParser>,
+    pub score: f32,
+}
+
+impl Sample {
+    pub fn read<R: BufRead>(reader: &mut R) -> io::Result<Self> {
+        let mut bytes = Vec::new();
+        for _ in 0..SAMPLE_LINES_COUNT {
+            if reader.read_until(b'\n', &mut bytes)? == 0 {
+                break;
+            }
+        }
+        Ok(Self { bytes })
+    }
+
+    /// Samples the first line and the complete lines already buffered after it,
+    /// so a pipe is not waited on until the whole sample was written.
+    pub fn read_buffered<R: Read>(reader: &mut BufReader<R>) -> io::Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_until(b'\n', &mut bytes)?;
+        for _ in 1..SAMPLE_LINES_COUNT {
+            if !reader.buffer().contains(&b'\n') {
+                break;
+            }
+            reader.read_until(b'\n', &mut bytes)?;
+        }
+        Ok(Self { bytes })
+    }
+
+    /// Empty lines are kept, the journal export format ends its entries with them.
+    fn lines(&self) -> Vec<String> {
+        self.bytes
+            .split(|byte| *byte == b'\n')
+            .map(|line| {
+                String::from_utf8_lossy(line)
+                    .trim_end_matches('\r')
+                    .to_string()
+            })
+            .collect()
+    }
+}
+
+pub fn detect_parser(sample: &Sample, registry: &ParserRegistry) -> Option<Detection> {
+    let lines = sample.lines();
+    let lines_count = lines.iter().filter(|line| !is_blank(line)).count();
+    if lines_count == 0 {
+        return None;
+    }
+    let mut best: Option<Detection> = None;
+    for mut parser in registry.parsers() {
+        let score = recognized_count(parser.as_mut(), &lines) as f32 / lines_count as f32;
+        log::info!("Format {} scored {score:.2}", parser.name());
+        if score > best.as_ref().map(|d| d.score).unwrap_or(MIN_SCORE) {
+            best = Some(Detection { parser, score });
+        }
+    }
+    best.map(|detection| Detection {
+        parser: registry.create(detection.parser.name()).unwrap(),
+        score: detection.score,
+    })
+}
+
+/// Counts the non-empty lines that end up in parsed entries: the lines producing them
+/// and the continuation lines appended to them, like stack traces. Lines the parser
+/// consumed without an entry, like a header row or part of a multi-line record,
+/// count only once an entry follows them, so a parser swallowing the rest of the
+/// sample gets no credit for it.
+fn recognized_count(parser: &mut dyn LogParser, lines: &[String]) -> usize {
+    let mut count = 0;
+    let mut consumed_count = 0;
+    let mut follows_entry = false;
+    for line in lines {
+        let entries = parser.parse_all(line);
+        if entries.is_empty() {
+            if is_blank(line) {
+                continue;
+            }
+            if !parser.is_continuation(line) {
+                consumed_count += 1;
+            } else if follows_entry {
+                count += 1;
+            }
+            continue;
+        }
+        follows_entry = entries.iter().any(|entry| !entry.is_unparsed());
+        if follows_entry {
+            count += consumed_count + usize::from(!is_blank(line));
+        }
+        consumed_count = 0;
+    }
+    count
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn detect(text: &str) -> Option<(String, f32)> {
+        let sample = Sample::read(&mut text.as_bytes()).unwrap();
+        let registry = ParserRegistry::new(&Config::default());
+        detect_parser(&sample, &registry)
+            .map(|detection| (detection.parser.name().to_string(), detection.score))
+    }
+
+    /// Returns its chunks one read at a time and fails when they run out.
+    struct Chunks(Vec<&'static [u8]>);
+
+    impl Read for Chunks {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            assert!(!self.0.is_empty(), "read past the written chunks");
+            let chunk = self.0.remove(0);
+            buf[..chunk.len()].copy_from_slice(chunk);
+            Ok(chunk.len())
+        }
+    }
+
+    #[test]
+    fn samples_only_buffered_lines() {
+        let mut reader = BufReader::new(Chunks(vec![b"first", b"\nsecond\nthird\npart"]));
+        let sample = Sample::read_buffered(&mut reader).unwrap();
+        assert_eq!(sample.bytes, b"first\nsecond\nthird\n");
+    }
+
+    #[test]
+    fn detects_env_logger() {
+        let text = "[2023-04-01T10:00:00Z INFO app] started\n\
+                    [2023-04-01T10:00:01Z WARN app] slow\n";
+        assert_eq!(detect(text), Some(("env_logger".to_string(), 1.0)));
+    }
+
+    #[test]
+    fn credits_header_and_multi_line_records() {
+        let text = "timestamp,level,message\n\
+                    2023-04-01T10:00:00Z,INFO,\"first\nsecond\"\n\
+                    2023-04-01T10:00:01Z,WARN,third\n";
+        assert_eq!(detect(text), Some(("csv".to_string(), 1.0)));
+    }
+
+    #[test]
+    fn credits_journal_fields_of_complete_entries() {
+        let text = "__REALTIME_TIMESTAMP=1680343200000000\nPRIORITY=6\nMESSAGE=started\n\n";
+        assert_eq!(detect(text), Some(("journal".to_string(), 1.0)));
+    }
+
+    #[test]
+    fn credits_continuation_lines_of_parsed_entries() {
+        let text = "2023-04-01 10:00:00,123 [main] ERROR com.example.Service - request failed\n\
+                    java.lang.IllegalStateException: closed\n\
+                    \tat com.example.Client.send(Client.java:42)\n\
+                    \tat com.example.Service.run(Service.java:7)\n\
+                    Caused by: java.io.IOException: reset\n\
+                    \t... 2 more\n";
+        assert_eq!(detect(text), Some(("java".to_string(), 1.0)));
+        let text = "[2023-04-01T10:00:00Z ERROR app] panicked\n\
+                    stack backtrace:\n   \
+                    0: std::panicking::begin_panic\n   \
+                    1: app::main\n";
+        assert_eq!(detect(text), Some(("env_logger".to_string(), 1.0)));
+    }
+
+    #[test]
+    fn requires_majority_of_lines() {
+        let text = "plain text\n\
+                    [2023-04-01T10:00:00Z INFO app] started\n";
+        assert_eq!(detect(text), None);
+        let text = format!("{text}[2023-04-01T10:00:01Z WARN app] slow\n");
+        assert_eq!(
+            detect(&text).map(|(name, _)| name),
+            Some("env_logger".to_string())
+        );
+    }
+
+    #[test]
+    fn ignores_lines_swallowed_by_an_unclosed_quote() {
+        let text = "timestamp,message\n\"unclosed\nplain text\nmore text\n";
+        assert_eq!(detect(text), None);
+    }
+}
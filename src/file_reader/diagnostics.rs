@@ -0,0 +1,74 @@
+use std::sync::{Arc, Mutex};
+
+/// Failures kept for the UI, later ones are only counted.
+const MAX_FAILURES: usize = 10_000;
+
+#[derive(Clone)]
+pub struct ParseFailure {
+    pub file: String,
+    /// Times the file was rotated or truncated before, line numbers restart then.
+    pub generation: usize,
+    pub line_number: usize,
+    pub reason: &'static str,
+    pub text: String,
+}
+
+/// Lines the readers could not attach to a parsed entry, shared with the UI.
+#[derive(Clone, Default)]
+pub struct Diagnostics {
+    failures: Arc<Mutex<Failures>>,
+}
+
+#[derive(Default)]
+struct Failures {
+    kept: Vec<ParseFailure>,
+    dropped_count: usize,
+}
+
+impl Diagnostics {
+    pub fn push(&self, failure: ParseFailure) {
+        let mut failures = self.failures.lock().unwrap();
+        if failures.kept.len() < MAX_FAILURES {
+            failures.kept.push(failure);
+        } else {
+            failures.dropped_count += 1;
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        let failures = self.failures.lock().unwrap();
+        failures.kept.len() + failures.dropped_count
+    }
+
+    /// The first failures, at most `MAX_FAILURES` of `count`.
+    pub fn failures(&self) -> Vec<ParseFailure> {
+        self.failures.lock().unwrap().kept.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failure(line_number: usize) -> ParseFailure {
+        ParseFailure {
+            file: "app.log".to_string(),
+            generation: 0,
+            line_number,
+            reason: "not indented",
+            text: "text".to_string(),
+        }
+    }
+
+    #[test]
+    fn counts_failures_beyond_the_cap() {
+        let diagnostics = Diagnostics::default();
+        for line_number in 0..MAX_FAILURES + 5 {
+            diagnostics.push(failure(line_number));
+        }
+        assert_eq!(diagnostics.count(), MAX_FAILURES + 5);
+        let failures = diagnostics.failures();
+        assert_eq!(failures.len(), MAX_FAILURES);
+        assert_eq!(failures.last().unwrap().line_number, MAX_FAILURES - 1);
+    }
+}
@@ -0,0 +1,232 @@
+use encoding_rs::{Decoder, Encoding};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::os::unix::fs::MetadataExt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+const RAW_BUFFER_SIZE: usize = 8 * 1024;
+
+pub struct FollowState {
+    path: PathBuf,
+    inode: u64,
+    encoding: Option<&'static Encoding>,
+    /// Bytes read from the file before decoding, shared with its reader.
+    position: Arc<AtomicU64>,
+}
+
+pub enum FileChange {
+    Rotated(File),
+    Truncated(File),
+}
+
+/// Counts the raw bytes read from the file, under the decoder.
+struct CountingReader {
+    file: File,
+    count: Arc<AtomicU64>,
+}
+
+/// Transcodes like `decode`, but reads the file again after EOF, which
+/// `DecodeReaderBytes` does not once it reached the end.
+struct FollowDecoder<R> {
+    reader: R,
+    encoding: Option<&'static Encoding>,
+    transcoding: Transcoding,
+    raw: Vec<u8>,
+    decoded: Vec<u8>,
+    position: usize,
+}
+
+enum Transcoding {
+    Undecided,
+    Decoder(Decoder),
+    Passthrough,
+}
+
+impl FollowState {
+    pub fn new(
+        path: PathBuf,
+        file: &File,
+        encoding: Option<&'static Encoding>,
+    ) -> io::Result<Self> {
+        Ok(Self {
+            path,
+            inode: file.metadata()?.ino(),
+            encoding,
+            position: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    /// Reader of the followed file from its start, it moves the position.
+    pub fn reader(&self, file: File) -> Box<dyn BufRead + Send> {
+        self.position.store(0, Ordering::Relaxed);
+        let file = CountingReader {
+            file,
+            count: self.position.clone(),
+        };
+        Box::new(BufReader::new(FollowDecoder::new(file, self.encoding)))
+    }
+
+    pub fn check_for_changes(&mut self) -> Option<FileChange> {
+        let metadata = std::fs::metadata(&self.path).ok()?;
+        let is_rotated = metadata.ino() != self.inode;
+        let is_truncated = !is_rotated && metadata.len() < self.position.load(Ordering::Relaxed);
+        if !is_rotated && !is_truncated {
+            return None;
+        }
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(error) => {
+                log::error!("Failed to reopen {:?}: {error:?}", self.path);
+                return None;
+            }
+        };
+        self.inode = file.metadata().map(|m| m.ino()).unwrap_or(metadata.ino());
+        if is_rotated {
+            log::info!("File {:?} was rotated", self.path);
+            Some(FileChange::Rotated(file))
+        } else {
+            log::info!("File {:?} was truncated", self.path);
+            Some(FileChange::Truncated(file))
+        }
+    }
+}
+
+impl Read for CountingReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let count = self.file.read(buf)?;
+        self.count.fetch_add(count as u64, Ordering::Relaxed);
+        Ok(count)
+    }
+}
+
+impl<R: Read> FollowDecoder<R> {
+    fn new(reader: R, encoding: Option<&'static Encoding>) -> Self {
+        Self {
+            reader,
+            encoding,
+            transcoding: Transcoding::Undecided,
+            raw: vec![0; RAW_BUFFER_SIZE],
+            decoded: Vec::new(),
+            position: 0,
+        }
+    }
+
+    /// A byte order mark in the first bytes takes precedence over `encoding`,
+    /// without both the bytes are passed as is.
+    fn transcoding(&self, start: &[u8]) -> Transcoding {
+        match (self.encoding, Encoding::for_bom(start)) {
+            (Some(encoding), _) => Transcoding::Decoder(encoding.new_decoder()),
+            (None, Some((encoding, _))) => {
+                Transcoding::Decoder(encoding.new_decoder_with_bom_removal())
+            }
+            (None, None) => Transcoding::Passthrough,
+        }
+    }
+}
+
+impl<R: Read> Read for FollowDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.decoded.len() {
+            let count = self.reader.read(&mut self.raw)?;
+            if count == 0 {
+                return Ok(0);
+            }
+            if let Transcoding::Undecided = self.transcoding {
+                self.transcoding = self.transcoding(&self.raw[..count]);
+            }
+            self.decoded.clear();
+            self.position = 0;
+            match &mut self.transcoding {
+                Transcoding::Decoder(decoder) => {
+                    let capacity = decoder
+                        .max_utf8_buffer_length(count)
+                        .unwrap_or(count * 3 + 16);
+                    let mut text = String::with_capacity(capacity);
+                    let _ = decoder.decode_to_string(&self.raw[..count], &mut text, false);
+                    self.decoded = text.into_bytes();
+                }
+                _ => self.decoded.extend_from_slice(&self.raw[..count]),
+            }
+        }
+        let count = buf.len().min(self.decoded.len() - self.position);
+        buf[..count].copy_from_slice(&self.decoded[self.position..self.position + count]);
+        self.position += count;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// Returns its chunks one read at a time, an empty chunk is an EOF.
+    struct Chunks(VecDeque<Vec<u8>>);
+
+    impl Read for Chunks {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let chunk = self.0.pop_front().unwrap_or_default();
+            buf[..chunk.len()].copy_from_slice(&chunk);
+            Ok(chunk.len())
+        }
+    }
+
+    fn read_lines(chunks: Vec<Vec<u8>>, encoding: Option<&'static Encoding>) -> Vec<String> {
+        let reads = chunks.len() * 2;
+        let mut reader = BufReader::new(FollowDecoder::new(Chunks(chunks.into()), encoding));
+        let mut lines = Vec::new();
+        let mut line = String::new();
+        for _ in 0..reads {
+            reader.read_line(&mut line).unwrap();
+            if line.ends_with('\n') {
+                lines.push(std::mem::take(&mut line));
+            }
+        }
+        lines
+    }
+
+    fn utf16le(text: &str) -> Vec<u8> {
+        text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+    }
+
+    #[test]
+    fn reads_again_after_eof() {
+        let chunks = vec![b"first\nsec".to_vec(), Vec::new(), b"ond\n".to_vec()];
+        assert_eq!(read_lines(chunks, None), ["first\n", "second\n"]);
+    }
+
+    #[test]
+    fn decodes_byte_order_mark_across_eofs() {
+        let mut first = vec![0xFF, 0xFE];
+        first.extend(utf16le("caf\u{e9}\n"));
+        let second = utf16le("appended\n");
+        let (odd, rest) = second.split_at(3);
+        let chunks = vec![first, Vec::new(), odd.to_vec(), Vec::new(), rest.to_vec()];
+        assert_eq!(read_lines(chunks, None), ["caf\u{e9}\n", "appended\n"]);
+    }
+
+    #[test]
+    fn decodes_configured_encoding() {
+        let chunks = vec![b"caf\xe9\n".to_vec()];
+        let encoding = Encoding::for_label(b"latin1");
+        assert_eq!(read_lines(chunks, encoding), ["caf\u{e9}\n"]);
+    }
+
+    #[test]
+    fn counts_raw_bytes() {
+        let path = std::env::temp_dir().join(format!("tuna-count-{}.log", std::process::id()));
+        let mut data = vec![0xFF, 0xFE];
+        data.extend(utf16le("line\n"));
+        std::fs::write(&path, &data).unwrap();
+        let file = File::open(&path).unwrap();
+        let state = FollowState::new(path.clone(), &file, None).unwrap();
+        let mut line = String::new();
+        state.reader(file).read_line(&mut line).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(line, "line\n");
+        assert_eq!(state.position.load(Ordering::Relaxed), data.len() as u64);
+    }
+}
@@ -1,19 +1,38 @@
-use chrono::{DateTime, FixedOffset, NaiveDateTime};
-use serde::Deserialize;
+use super::timestamp::TimestampParser;
+use crate::config::JsonMapping;
+use chrono::{DateTime, FixedOffset};
+use itertools::Itertools;
+use regex::Regex;
+use serde_json::Value;
 use std::{
     collections::hash_map::DefaultHasher,
     hash::{Hash, Hasher},
 };
 
+const PLAIN_TEXT_SOURCE: &str = "text";
+const UNPARSED_SOURCE: &str = "unparsed";
+/// Width of `date_time`, e.g. `12:30:45.123`.
+const DATE_TIME_WIDTH: usize = 12;
+
 #[derive(Clone)]
 pub struct LogEntry {
     pub message: String,
-    pub date: DateTime<FixedOffset>,
+    pub date: Option<DateTime<FixedOffset>>,
     pub date_time: String,
+    pub line_number: Option<usize>,
     pub source: Source,
+    pub file: Option<Source>,
+    pub level: Option<Level>,
+    pub fields: Vec<(String, String)>,
+    pub stack_trace: Vec<String>,
     pub one_line_message: String,
+    /// Message and stack trace lines, searched by `matches_query`.
     pub lower_case_message: String,
+    pub lower_case_fields: String,
     pub lines_count: usize,
+    pub is_separator: bool,
+    /// Set when invalid bytes were replaced with U+FFFD while decoding.
+    pub has_replacement_chars: bool,
     date_full: Option<String>,
 }
 
@@ -23,8 +42,18 @@ pub struct Source {
     pub hash: u64,
 }
 
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
 impl Source {
-    fn new(name: String) -> Self {
+    pub fn new(name: String) -> Self {
         let mut hasher = DefaultHasher::new();
         name.hash(&mut hasher);
         Self {
@@ -36,86 +65,499 @@ impl Source {
 
 impl From<ExternalLogMessage> for LogEntry {
     fn from(value: ExternalLogMessage) -> Self {
-        let date_time = value.date.format("%T%.3f");
-        let one_line_message = value.message.lines().next().unwrap().into();
-        let lower_case_message = value.message.to_lowercase();
-        let lines_count = value.message.lines().count();
+        LogEntry::new(
+            value.message,
+            Some(value.date),
+            value.source,
+            value.level,
+            value.fields,
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct ExternalLogMessage {
+    pub message: String,
+    pub date: DateTime<FixedOffset>,
+    pub source: String,
+    pub level: Option<Level>,
+    pub fields: Vec<(String, String)>,
+}
+
+impl ExternalLogMessage {
+    /// Maps a decoded JSON object, `default_date` is used when it has no timestamp key.
+    pub fn from_json(
+        value: &Value,
+        mapping: &JsonMapping,
+        timestamps: &TimestampParser,
+        default_date: Option<DateTime<FixedOffset>>,
+    ) -> Option<Self> {
+        let message = json_lookup(value, &mapping.message).map(json_to_string)?;
+        let date = json_lookup(value, &mapping.timestamp)
+            .and_then(|v| timestamps.parse_json(v))
+            .or(default_date)?;
+        let source = json_lookup(value, &mapping.source)
+            .map(json_to_string)
+            .unwrap_or_else(|| "-".to_string());
+        let (level_key, level) = [mapping.level.as_str(), "severity"]
+            .into_iter()
+            .find_map(|key| json_lookup(value, key).map(|level| (key, level)))
+            .map(|(key, level)| (key, Level::from_json(level)))
+            .unwrap_or_default();
+        let mapped_keys = [
+            mapping.message.as_str(),
+            mapping.timestamp.as_str(),
+            mapping.source.as_str(),
+            level_key,
+        ];
+        let mut fields = Vec::new();
+        collect_json_fields(value, "", &mapped_keys, &mut fields);
+        Some(ExternalLogMessage {
+            message,
+            date,
+            source,
+            level,
+            fields,
+        })
+    }
+}
+
+impl LogEntry {
+    fn new(
+        message: String,
+        date: Option<DateTime<FixedOffset>>,
+        source: String,
+        level: Option<Level>,
+        fields: Vec<(String, String)>,
+    ) -> Self {
+        let date_time = date
+            .map(|date| date.format("%T%.3f").to_string())
+            .unwrap_or_default();
+        let one_line_message = message.lines().next().unwrap_or_default().into();
+        let lower_case_message = message.to_lowercase();
+        let lines_count = message.lines().count();
+        let lower_case_fields = fields
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .join(" ")
+            .to_lowercase();
+        let has_replacement_chars = message.contains(char::REPLACEMENT_CHARACTER)
+            || lower_case_fields.contains(char::REPLACEMENT_CHARACTER);
         Self {
-            message: value.message,
-            date: value.date,
-            date_time: date_time.to_string(),
-            source: Source::new(value.source),
+            message,
+            date,
+            date_time,
+            line_number: None,
+            source: Source::new(source),
+            file: None,
+            level,
+            fields,
+            stack_trace: Vec::new(),
             one_line_message,
             lower_case_message,
+            lower_case_fields,
             lines_count,
+            is_separator: false,
+            has_replacement_chars,
             date_full: None,
         }
     }
-}
 
-#[derive(Deserialize, Debug)]
-struct ExternalLogMessage {
-    message: String,
-    #[serde(with = "date_parse")]
-    date: DateTime<FixedOffset>,
-    source: String,
-}
+    /// Undated text line, shown with its line number instead of the time.
+    pub fn plain(message: &str) -> Self {
+        LogEntry::new(
+            message.to_string(),
+            None,
+            PLAIN_TEXT_SOURCE.to_string(),
+            Level::find_bracketed(message),
+            Vec::new(),
+        )
+    }
 
-impl LogEntry {
-    pub fn from_raw(log: &str) -> Option<Self> {
+    /// Line that failed to parse and is not a continuation of the previous entry,
+    /// it takes the previous entry date to keep its place among merged files.
+    pub fn unparsed(message: &str, date: Option<DateTime<FixedOffset>>) -> Self {
+        LogEntry::new(
+            message.to_string(),
+            date,
+            UNPARSED_SOURCE.to_string(),
+            Level::find_bracketed(message),
+            Vec::new(),
+        )
+    }
+
+    pub fn is_unparsed(&self) -> bool {
+        self.source.name == UNPARSED_SOURCE
+    }
+
+    pub fn from_raw(log: &str, timestamps: &TimestampParser) -> Option<Self> {
         let mut iter = log.splitn(3, |c: char| c.is_whitespace());
         let (date, source, message) = (iter.next()?, iter.next()?, iter.next()?);
-        if date.is_empty() || source.len() < 3 {
+        let source = source.strip_prefix('[')?.strip_suffix("]:")?;
+        if date.is_empty() {
             return None;
         }
-        NaiveDateTime::parse_from_str(&date[0..date.len() - 1], "%Y-%m-%dT%H:%M:%S%.3f")
+        timestamps
+            .parse(date)
             .map(|date| ExternalLogMessage {
                 message: message.to_string(),
-                date: DateTime::<FixedOffset>::from_utc(date, FixedOffset::east_opt(0).unwrap()),
-                source: source[1..source.len() - 2].to_string(),
+                date,
+                source: source.to_string(),
+                level: Level::find_bracketed(message),
+                fields: Vec::new(),
             })
             .map(LogEntry::from)
-            .ok()
     }
 
-    pub fn from_json(log: &str) -> Option<Self> {
-        let result = serde_json::from_str::<ExternalLogMessage>(log).map(LogEntry::from);
-        match result {
-            Ok(entry) => Some(entry),
-            Err(error) => {
-                log::error!("Failed to parse log {log:?} with error: {error:?}");
-                None
+    pub fn from_json(
+        log: &str,
+        mapping: &JsonMapping,
+        timestamps: &TimestampParser,
+    ) -> Option<Self> {
+        let value = serde_json::from_str::<Value>(log).ok()?;
+        ExternalLogMessage::from_json(&value, mapping, timestamps, None).map(LogEntry::from)
+    }
+
+    pub fn from_logfmt(log: &str, source_key: &str, timestamps: &TimestampParser) -> Option<Self> {
+        let mut date = None;
+        let mut message = None;
+        let mut source = None;
+        let mut level = None;
+        let mut fields = Vec::new();
+        for (key, value) in parse_logfmt(log)? {
+            match key {
+                _ if key == source_key && source.is_none() => source = Some(value),
+                "time" | "ts" if date.is_none() => match timestamps.parse(&value) {
+                    Some(value) => date = Some(value),
+                    None => fields.push((key.to_string(), value)),
+                },
+                "msg" if message.is_none() => message = Some(value),
+                "level" if level.is_none() => match Level::parse(&value) {
+                    Some(value) => level = Some(value),
+                    None => fields.push((key.to_string(), value)),
+                },
+                _ => fields.push((key.to_string(), value)),
             }
         }
+        Some(LogEntry::from(ExternalLogMessage {
+            message: message.unwrap_or_default(),
+            date: date?,
+            source: source.unwrap_or_else(|| "-".to_string()),
+            level,
+            fields,
+        }))
+    }
+
+    pub fn from_regex(log: &str, regex: &Regex, timestamps: &TimestampParser) -> Option<Self> {
+        let captures = regex.captures(log)?;
+        let date = timestamps.parse(captures.name("date")?.as_str())?;
+        let message = captures.name("message")?.as_str().to_string();
+        let source = captures
+            .name("source")
+            .map(|source| source.as_str().to_string())
+            .unwrap_or_else(|| "-".to_string());
+        let level = match captures.name("level") {
+            Some(level) => Level::parse(level.as_str()),
+            None => Level::find_bracketed(&message),
+        };
+        let fields = regex
+            .capture_names()
+            .flatten()
+            .filter(|name| !matches!(*name, "date" | "message" | "source" | "level"))
+            .filter_map(|name| {
+                let value = captures.name(name)?;
+                Some((name.to_string(), value.as_str().to_string()))
+            })
+            .collect();
+        Some(LogEntry::from(ExternalLogMessage {
+            message,
+            date,
+            source,
+            level,
+            fields,
+        }))
+    }
+
+    pub fn separator(message: &str, date: DateTime<FixedOffset>) -> Self {
+        let mut entry = LogEntry::from(ExternalLogMessage {
+            message: message.to_string(),
+            date,
+            source: "tuna".to_string(),
+            level: None,
+            fields: Vec::new(),
+        });
+        entry.is_separator = true;
+        entry
+    }
+
+    pub fn set_line_number(&mut self, line_number: usize) {
+        self.line_number = Some(line_number);
+        if self.date.is_none() {
+            self.date_time = format!("{line_number:>DATE_TIME_WIDTH$}");
+        }
     }
 
     pub fn append(&mut self, message: &str) {
+        self.has_replacement_chars |= message.contains(char::REPLACEMENT_CHARACTER);
         self.message.push('\n');
         self.message.push_str(message);
+        self.lower_case_message.push('\n');
+        self.lower_case_message.push_str(&message.to_lowercase());
         self.lines_count += 1;
     }
 
+    /// The first stack trace line also takes over the last message line,
+    /// which is the exception header printed right before the frames.
+    /// The header keeps its place in the search text, which ends with the trace.
+    pub fn push_stack_trace(&mut self, line: &str) {
+        if self.stack_trace.is_empty() && self.lines_count > 1 {
+            if let Some(index) = self.message.rfind('\n') {
+                let header = self.message.split_off(index);
+                self.stack_trace.push(header[1..].to_string());
+                self.lines_count -= 1;
+            }
+        }
+        self.has_replacement_chars |= line.contains(char::REPLACEMENT_CHARACTER);
+        self.lower_case_message.push('\n');
+        self.lower_case_message.push_str(&line.to_lowercase());
+        self.stack_trace.push(line.to_string());
+    }
+
+    /// Simple class name of an `...Exception: message` or `...Error: message`
+    /// header, `None` when the trace starts with frames.
+    pub fn exception_name(&self) -> Option<&str> {
+        let header = self.stack_trace.first()?.trim();
+        let class = header.split(':').next().unwrap_or(header);
+        let name = class.rsplit('.').next().unwrap_or(class);
+        let is_exception = name.ends_with("Exception") || name.ends_with("Error");
+        (is_exception && !class.contains(char::is_whitespace)).then_some(name)
+    }
+
+    pub fn field(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field_key, _)| field_key == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn matches_query(&self, query: &str) -> bool {
+        self.lower_case_message.contains(query) || self.lower_case_fields.contains(query)
+    }
+
     pub fn date_full(&mut self) -> String {
         if let Some(date) = self.date_full.clone() {
             date
         } else {
-            self.date_full = Some(self.date.format("%c").to_string());
+            let date_full = match (self.date, self.line_number) {
+                (Some(date), _) => date.format("%c").to_string(),
+                (None, Some(line_number)) => format!("line {line_number}"),
+                (None, None) => String::new(),
+            };
+            self.date_full = Some(date_full);
             self.date_full.clone().unwrap()
         }
     }
 }
 
-mod date_parse {
-    use chrono::{DateTime, FixedOffset};
-    use serde::{Deserialize, Deserializer};
-
-    pub fn deserialize<'a, D>(deserializer: D) -> Result<DateTime<FixedOffset>, D::Error>
-    where
-        D: Deserializer<'a>,
-    {
-        let string = String::deserialize(deserializer)?;
-        let format = "%e %b %Y %H:%M:%S%.3f %z";  
-        DateTime::parse_from_str(string.as_str(), format)
-            .map_err(serde::de::Error::custom)
+impl Level {
+    pub const ALL: [Level; 6] = [
+        Level::Trace,
+        Level::Debug,
+        Level::Info,
+        Level::Warn,
+        Level::Error,
+        Level::Fatal,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Level::Trace => "TRACE",
+            Level::Debug => "DEBUG",
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+            Level::Error => "ERROR",
+            Level::Fatal => "FATAL",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let level = match value.trim().to_lowercase().as_str() {
+            "trace" | "verbose" | "finest" | "finer" => Level::Trace,
+            "debug" | "dbg" | "fine" => Level::Debug,
+            "info" | "information" | "notice" => Level::Info,
+            "warn" | "warning" => Level::Warn,
+            "error" | "err" | "severe" => Level::Error,
+            "fatal" | "critical" | "crit" | "panic" | "dpanic" | "alert" | "emerg" => Level::Fatal,
+            _ => return None,
+        };
+        Some(level)
+    }
+
+    /// Numeric levels are syslog severities, as in GELF, or bunyan/pino levels.
+    pub fn from_number(value: i64) -> Option<Self> {
+        let level = match value {
+            0..=7 => return Level::from_syslog_severity(value as u8),
+            10 => Level::Trace,
+            20 => Level::Debug,
+            30 => Level::Info,
+            40 => Level::Warn,
+            50 => Level::Error,
+            60 => Level::Fatal,
+            _ => return None,
+        };
+        Some(level)
+    }
+
+    pub fn from_syslog_severity(severity: u8) -> Option<Self> {
+        let level = match severity {
+            0..=2 => Level::Fatal,
+            3 => Level::Error,
+            4 => Level::Warn,
+            5 | 6 => Level::Info,
+            7 => Level::Debug,
+            _ => return None,
+        };
+        Some(level)
+    }
+
+    pub fn from_logcat_priority(priority: &str) -> Option<Self> {
+        let level = match priority {
+            "V" => Level::Trace,
+            "D" => Level::Debug,
+            "I" => Level::Info,
+            "W" => Level::Warn,
+            "E" => Level::Error,
+            "F" | "A" => Level::Fatal,
+            _ => return None,
+        };
+        Some(level)
+    }
+
+    pub fn from_otel_severity(severity: i64) -> Option<Self> {
+        let level = match severity {
+            1..=4 => Level::Trace,
+            5..=8 => Level::Debug,
+            9..=12 => Level::Info,
+            13..=16 => Level::Warn,
+            17..=20 => Level::Error,
+            21..=24 => Level::Fatal,
+            _ => return None,
+        };
+        Some(level)
+    }
+
+    pub fn from_json(value: &Value) -> Option<Self> {
+        match value {
+            Value::String(value) => Level::parse(value),
+            Value::Number(value) => value.as_i64().and_then(Level::from_number),
+            _ => None,
+        }
+    }
+
+    pub fn find_bracketed(message: &str) -> Option<Self> {
+        message
+            .split('[')
+            .skip(1)
+            .take(3)
+            .filter_map(|token| token.split_once(']'))
+            .find_map(|(token, _)| Level::parse(token))
+    }
+}
+
+fn json_lookup<'v>(value: &'v Value, path: &str) -> Option<&'v Value> {
+    let object = value.as_object()?;
+    if let Some(value) = object.get(path) {
+        return Some(value);
+    }
+    path.match_indices('.').find_map(|(index, _)| {
+        let nested = object.get(&path[..index])?;
+        json_lookup(nested, &path[index + 1..])
+    })
+}
+
+pub(crate) fn parse_logfmt(line: &str) -> Option<Vec<(&str, String)>> {
+    let mut pairs = Vec::new();
+    let mut has_values = false;
+    let mut rest = line.trim_start();
+    while !rest.is_empty() {
+        let key_end = rest
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let key = &rest[..key_end];
+        let is_valid_key = key
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '/' | '@'));
+        if key.is_empty() || !is_valid_key {
+            return None;
+        }
+        rest = &rest[key_end..];
+        let value = match rest.strip_prefix('=') {
+            Some(value) => {
+                let (value, remaining) = parse_logfmt_value(value)?;
+                has_values = true;
+                rest = remaining;
+                value
+            }
+            None => String::new(),
+        };
+        pairs.push((key, value));
+        rest = rest.trim_start();
+    }
+    has_values.then_some(pairs)
+}
+
+fn parse_logfmt_value(value: &str) -> Option<(String, &str)> {
+    let Some(quoted) = value.strip_prefix('"') else {
+        let end = value.find(char::is_whitespace).unwrap_or(value.len());
+        return Some((value[..end].to_string(), &value[end..]));
+    };
+    let mut result = String::new();
+    let mut chars = quoted.char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => return Some((result, &quoted[index + 1..])),
+            '\\' => match chars.next()?.1 {
+                'n' => result.push('\n'),
+                'r' => result.push('\r'),
+                't' => result.push('\t'),
+                c => result.push(c),
+            },
+            c => result.push(c),
+        }
+    }
+    None
+}
+
+fn collect_json_fields(
+    value: &Value,
+    prefix: &str,
+    mapped_keys: &[&str],
+    fields: &mut Vec<(String, String)>,
+) {
+    let Some(object) = value.as_object() else {
+        return;
+    };
+    for (key, value) in object {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        if mapped_keys.contains(&path.as_str()) {
+            continue;
+        }
+        match value {
+            Value::Object(object) if !object.is_empty() => {
+                collect_json_fields(value, &path, mapped_keys, fields)
+            }
+            value => fields.push((path, json_to_string(value))),
+        }
+    }
+}
+
+fn json_to_string(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
     }
 }
@@ -0,0 +1,146 @@
+use super::log_entry::LogEntry;
+use crossbeam_channel::{Receiver, Select, Sender, TryRecvError};
+use std::time::Duration;
+
+const STREAMING_WAIT_TIMEOUT: Duration = Duration::from_millis(100);
+
+enum Head {
+    Waiting,
+    Idle,
+    Entry(Box<LogEntry>),
+    Finished,
+}
+
+pub fn merge(
+    receivers: Vec<Receiver<LogEntry>>,
+    is_streaming: bool,
+    sender: Sender<LogEntry>,
+    callback: cursive::CbSink,
+) {
+    let mut heads = receivers.iter().map(|_| Head::Waiting).collect::<Vec<_>>();
+    loop {
+        for (head, receiver) in heads.iter_mut().zip(receivers.iter()) {
+            if matches!(head, Head::Entry(_) | Head::Finished) {
+                continue;
+            }
+            if !is_streaming {
+                *head = receiver
+                    .recv()
+                    .map(|entry| Head::Entry(Box::new(entry)))
+                    .unwrap_or(Head::Finished);
+                continue;
+            }
+            match receiver.try_recv() {
+                Ok(entry) => *head = Head::Entry(Box::new(entry)),
+                Err(TryRecvError::Disconnected) => *head = Head::Finished,
+                Err(TryRecvError::Empty) => {}
+            }
+        }
+        if heads.iter().all(|head| matches!(head, Head::Finished)) {
+            break;
+        }
+        if is_streaming && wait_for_pending(&mut heads, &receivers) {
+            continue;
+        }
+        let earliest = heads
+            .iter()
+            .enumerate()
+            .filter_map(|(index, head)| match head {
+                Head::Entry(entry) => Some((index, entry.date)),
+                _ => None,
+            })
+            // Undated entries, like preamble lines, go first.
+            .min_by_key(|(_, date)| *date)
+            .map(|(index, _)| index);
+        if let Some(index) = earliest {
+            if let Head::Entry(entry) = std::mem::replace(&mut heads[index], Head::Waiting) {
+                super::send_entry(*entry, &sender, &callback);
+            }
+        }
+    }
+    callback.send(Box::new(cursive::Cursive::noop)).unwrap();
+}
+
+fn wait_for_pending(heads: &mut [Head], receivers: &[Receiver<LogEntry>]) -> bool {
+    let has_entries = heads.iter().any(|head| matches!(head, Head::Entry(_)));
+    let waiting = heads
+        .iter()
+        .enumerate()
+        .filter(|(_, head)| match head {
+            Head::Waiting => true,
+            Head::Idle => !has_entries,
+            _ => false,
+        })
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    if waiting.is_empty() {
+        return false;
+    }
+    let mut select = Select::new();
+    waiting.iter().for_each(|index| {
+        select.recv(&receivers[*index]);
+    });
+    if select.ready_timeout(STREAMING_WAIT_TIMEOUT).is_ok() {
+        return true;
+    }
+    waiting
+        .into_iter()
+        .for_each(|index| heads[index] = Head::Idle);
+    !has_entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    fn entry(message: &str, date: &str) -> LogEntry {
+        LogEntry::separator(message, DateTime::parse_from_rfc3339(date).unwrap())
+    }
+
+    fn messages(receiver: &Receiver<LogEntry>) -> Vec<String> {
+        receiver.try_iter().map(|entry| entry.message).collect()
+    }
+
+    #[test]
+    fn merges_by_date_with_undated_entries_first() {
+        let (first_sender, first) = crossbeam_channel::unbounded();
+        let (second_sender, second) = crossbeam_channel::unbounded();
+        first_sender
+            .send(entry("a1", "2023-04-01T10:00:00Z"))
+            .unwrap();
+        first_sender
+            .send(entry("a2", "2023-04-01T10:00:02Z"))
+            .unwrap();
+        second_sender.send(LogEntry::plain("preamble")).unwrap();
+        second_sender
+            .send(entry("b1", "2023-04-01T10:00:01Z"))
+            .unwrap();
+        drop((first_sender, second_sender));
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let (callback, _callbacks) = crossbeam_channel::unbounded();
+
+        merge(vec![first, second], false, sender, callback);
+
+        assert_eq!(messages(&receiver), ["preamble", "a1", "b1", "a2"]);
+    }
+
+    #[test]
+    fn streams_entries_while_another_input_is_idle() {
+        let (first_sender, first) = crossbeam_channel::unbounded();
+        let (second_sender, second) = crossbeam_channel::unbounded::<LogEntry>();
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let (callback, _callbacks) = crossbeam_channel::unbounded();
+        let merging =
+            std::thread::spawn(move || merge(vec![first, second], true, sender, callback));
+
+        first_sender
+            .send(entry("a1", "2023-04-01T10:00:00Z"))
+            .unwrap();
+        let received = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        drop((first_sender, second_sender));
+        merging.join().unwrap();
+
+        assert_eq!(received.message, "a1");
+    }
+}
@@ -1,45 +1,325 @@
+use chrono::Utc;
+use continuation::ContinuationPolicy;
 use crossbeam_channel::Sender;
-use log_entry::LogEntry;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use diagnostics::{Diagnostics, ParseFailure};
+use encoding_rs::Encoding;
+use encoding_rs_io::{DecodeReaderBytes, DecodeReaderBytesBuilder};
+use follow::{FileChange, FollowState};
+use log_entry::{LogEntry, Source};
+use parser::LogParser;
+use std::io::{BufRead, BufReader, Read};
+use std::time::Duration;
 
+pub mod continuation;
+pub mod detect;
+pub mod diagnostics;
+pub mod follow;
 pub mod log_entry;
+pub mod merge;
+pub mod parser;
+pub mod timestamp;
 
-pub fn read_file(
-    file: File,
-    is_raw_file: bool,
-    sender: Sender<LogEntry>,
-    callback: cursive::CbSink,
-) {
-    let reader = BufReader::new(file);
-    let parser = if is_raw_file {
-        LogEntry::from_raw
-    } else {
-        LogEntry::from_json
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Idle polls before the pending entry is shown, its continuation lines may still be written.
+const FLUSH_IDLE_POLLS: u32 = 4;
+const PREAMBLE_REASON: &str = "before the first entry";
+const FLUSHED_REASON: &str = "after its entry was flushed";
+
+pub struct LogInput {
+    pub name: String,
+    pub reader: Box<dyn BufRead + Send>,
+    pub follow: Option<FollowState>,
+    pub parser: Box<dyn LogParser>,
+    pub continuation: ContinuationPolicy,
+    pub diagnostics: Diagnostics,
+}
+
+pub fn read_file(input: LogInput, sender: Sender<LogEntry>, callback: cursive::CbSink) {
+    let LogInput {
+        name,
+        mut reader,
+        mut follow,
+        mut parser,
+        continuation,
+        diagnostics,
+    } = input;
+    let file = Source::new(name);
+    let emit = |mut entry: LogEntry| {
+        entry.file = Some(file.clone());
+        send_entry(entry, &sender, &callback);
     };
-    let mut iterator = reader
-        .lines()
-        .filter_map(|result| match result {
+    let mut pending_entry: Option<LogEntry> = None;
+    let mut line = Vec::new();
+    let mut line_number = 0;
+    let mut generation = 0;
+    let mut idle_polls = 0;
+    let mut flushed_date = None;
+    let mut change = None;
+    loop {
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) => {
+                let Some(follow) = follow.as_mut() else {
+                    break;
+                };
+                match change.take() {
+                    // The unterminated last line of the old file is read as a complete one.
+                    Some(pending) if !line.is_empty() => change = Some(pending),
+                    Some(pending) => {
+                        let (file, message) = match pending {
+                            FileChange::Rotated(file) => (file, "file rotated"),
+                            FileChange::Truncated(file) => (file, "file truncated"),
+                        };
+                        for mut entry in parser.finish() {
+                            entry.set_line_number(line_number);
+                            if let Some(entry) = pending_entry.replace(entry) {
+                                emit(entry);
+                            }
+                        }
+                        reader = follow.reader(file);
+                        line_number = 0;
+                        generation += 1;
+                        let date = pending_entry
+                            .as_ref()
+                            .map(|entry| entry.date)
+                            .or(flushed_date.take())
+                            .flatten()
+                            .unwrap_or_else(|| Utc::now().into());
+                        if let Some(entry) = pending_entry.take() {
+                            emit(entry);
+                        }
+                        emit(LogEntry::separator(message, date));
+                        continue;
+                    }
+                    None => {
+                        // The old file is read to its end before switching, lines may have
+                        // been written to it after the last read.
+                        change = follow.check_for_changes();
+                        if change.is_some() {
+                            continue;
+                        }
+                        idle_polls += 1;
+                        if idle_polls == FLUSH_IDLE_POLLS {
+                            if let Some(entry) = pending_entry.take() {
+                                flushed_date = Some(entry.date);
+                                emit(entry);
+                            }
+                        }
+                        std::thread::sleep(FOLLOW_POLL_INTERVAL);
+                        continue;
+                    }
+                }
+            }
+            Ok(_) => {
+                idle_polls = 0;
+                if follow.is_some() && !line.ends_with(b"\n") {
+                    continue;
+                }
+            }
             Err(error) => {
                 log::error!("Read log file failed: {error:?}");
-                None
+                line.clear();
+                continue;
             }
-            Ok(line) => Some(parser(&line).ok_or(line)),
-        })
-        .peekable();
-
-    while let Some(entry) = iterator.next() {
-        let Ok(mut entry) = entry else {
-            continue;
-        };
-        while let Some(Err(line)) = iterator.peek() {
-            entry.append(line);
-            iterator.next();
         }
-        if sender.is_full() {
-            callback.send(Box::new(cursive::Cursive::noop)).unwrap();
+        line_number += 1;
+        let entries = parser.parse_bytes(&line);
+        let text = String::from_utf8_lossy(&line);
+        let text = text.trim_end_matches(['\n', '\r']);
+        if entries.is_empty() && parser.is_continuation(text) {
+            let report = |reason| {
+                diagnostics.push(ParseFailure {
+                    file: file.name.clone(),
+                    generation,
+                    line_number,
+                    reason,
+                    text: text.to_string(),
+                })
+            };
+            match pending_entry.as_mut() {
+                Some(entry) => match continuation.rejection(entry, text) {
+                    None => parser.append(entry, text),
+                    Some(_) if text.trim().is_empty() => {}
+                    Some(reason) => {
+                        report(reason);
+                        let mut unparsed = LogEntry::unparsed(text, entry.date);
+                        unparsed.set_line_number(line_number);
+                        if let Some(entry) = pending_entry.replace(unparsed) {
+                            emit(entry);
+                        }
+                    }
+                },
+                None if text.trim().is_empty() => {}
+                None if flushed_date.is_some() => {
+                    report(FLUSHED_REASON);
+                    let mut unparsed = LogEntry::unparsed(text, flushed_date.flatten());
+                    unparsed.set_line_number(line_number);
+                    pending_entry = Some(unparsed);
+                }
+                None => {
+                    report(PREAMBLE_REASON);
+                    let mut entry = LogEntry::plain(text);
+                    entry.set_line_number(line_number);
+                    emit(entry);
+                }
+            }
+        }
+        for mut entry in entries {
+            entry.set_line_number(line_number);
+            flushed_date = None;
+            if let Some(entry) = pending_entry.replace(entry) {
+                emit(entry);
+            }
+        }
+        line.clear();
+    }
+    for mut entry in parser.finish() {
+        entry.set_line_number(line_number);
+        if let Some(entry) = pending_entry.replace(entry) {
+            emit(entry);
         }
-        sender.send(entry).unwrap();
+    }
+    if let Some(entry) = pending_entry {
+        emit(entry);
     }
     callback.send(Box::new(cursive::Cursive::noop)).unwrap();
 }
+
+/// Transcodes `encoding` to UTF-8, a byte order mark takes precedence over it.
+/// Without both the bytes are passed as is and invalid UTF-8 is replaced later.
+pub fn decode<R: Read>(
+    reader: R,
+    encoding: Option<&'static Encoding>,
+) -> BufReader<DecodeReaderBytes<R, Vec<u8>>> {
+    let reader = DecodeReaderBytesBuilder::new()
+        .encoding(encoding)
+        .bom_override(true)
+        .build(reader);
+    BufReader::new(reader)
+}
+
+fn send_entry(entry: LogEntry, sender: &Sender<LogEntry>, callback: &cursive::CbSink) {
+    if sender.is_full() {
+        callback.send(Box::new(cursive::Cursive::noop)).unwrap();
+    }
+    sender.send(entry).unwrap();
+    if sender.len() == 1 {
+        callback.send(Box::new(cursive::Cursive::noop)).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ContinuationConfig;
+    use parser::EnvLoggerParser;
+    use std::fs::{File, OpenOptions};
+    use std::io::{Cursor, Write};
+
+    const ENTRY: &str = "[2023-04-01T10:00:00Z ERROR app] request failed\n";
+
+    fn input(reader: Box<dyn BufRead + Send>, follow: Option<FollowState>) -> LogInput {
+        LogInput {
+            name: "app.log".to_string(),
+            reader,
+            follow,
+            parser: Box::new(EnvLoggerParser),
+            continuation: ContinuationPolicy::new(&ContinuationConfig::default()).unwrap(),
+            diagnostics: Diagnostics::default(),
+        }
+    }
+
+    #[test]
+    fn appends_continuation_lines_and_reports_preamble() {
+        let text = format!("preamble\n{ENTRY}  caused by timeout\n");
+        let log_input = input(Box::new(Cursor::new(text)), None);
+        let diagnostics = log_input.diagnostics.clone();
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let (callback, _callbacks) = crossbeam_channel::unbounded();
+        read_file(log_input, sender, callback);
+
+        let entries: Vec<_> = receiver.try_iter().collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].message, "preamble");
+        assert_eq!(entries[1].message, "request failed\n  caused by timeout");
+        assert_eq!(entries[1].line_number, Some(2));
+        let failures = diagnostics.failures();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].reason, PREAMBLE_REASON);
+    }
+
+    fn follow(name: &str) -> (std::path::PathBuf, LogInput) {
+        let path = std::env::temp_dir().join(format!("tuna-{name}-{}.log", std::process::id()));
+        std::fs::write(&path, ENTRY).unwrap();
+        let file = File::open(&path).unwrap();
+        let follow = FollowState::new(path.clone(), &file, None).unwrap();
+        (path, input(follow.reader(file), Some(follow)))
+    }
+
+    #[test]
+    fn keeps_pending_entry_across_idle_polls() {
+        let (path, log_input) = follow("idle");
+        let diagnostics = log_input.diagnostics.clone();
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let (callback, _callbacks) = crossbeam_channel::unbounded();
+        std::thread::spawn(move || read_file(log_input, sender, callback));
+
+        std::thread::sleep(FOLLOW_POLL_INTERVAL * 2);
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"  caused by timeout\n").unwrap();
+        let entry = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(entry.message, "request failed\n  caused by timeout");
+        assert_eq!(diagnostics.count(), 0);
+    }
+
+    #[test]
+    fn restarts_line_numbers_after_rotation() {
+        let (path, log_input) = follow("rotation");
+        let diagnostics = log_input.diagnostics.clone();
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let (callback, _callbacks) = crossbeam_channel::unbounded();
+        std::thread::spawn(move || read_file(log_input, sender, callback));
+
+        std::thread::sleep(FOLLOW_POLL_INTERVAL);
+        let rotated_path = path.with_extension("log.1");
+        std::fs::rename(&path, &rotated_path).unwrap();
+        std::fs::write(&path, format!("restarted\n{ENTRY}")).unwrap();
+        let receive = || receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        let (entry, separator, preamble) = (receive(), receive(), receive());
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&rotated_path).unwrap();
+
+        assert_eq!(entry.line_number, Some(1));
+        assert!(separator.is_separator);
+        assert_eq!(preamble.message, "restarted");
+        let failures = diagnostics.failures();
+        assert_eq!(failures.len(), 1);
+        assert_eq!((failures[0].generation, failures[0].line_number), (1, 1));
+    }
+
+    #[test]
+    fn reads_unterminated_last_line_before_rotation() {
+        let (path, log_input) = follow("unterminated");
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"[2023-04-01T10:00:01Z WARN app] last")
+            .unwrap();
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let (callback, _callbacks) = crossbeam_channel::unbounded();
+        std::thread::spawn(move || read_file(log_input, sender, callback));
+
+        std::thread::sleep(FOLLOW_POLL_INTERVAL);
+        let rotated_path = path.with_extension("log.1");
+        std::fs::rename(&path, &rotated_path).unwrap();
+        std::fs::write(&path, ENTRY).unwrap();
+        let receive = || receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        let (first, last, separator) = (receive(), receive(), receive());
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&rotated_path).unwrap();
+
+        assert_eq!(first.message, "request failed");
+        assert_eq!(last.message, "last");
+        assert_eq!(last.line_number, Some(2));
+        assert!(separator.is_separator);
+    }
+}
@@ -0,0 +1,158 @@
+use super::LogParser;
+use crate::file_reader::log_entry::{ExternalLogMessage, Level, LogEntry};
+use chrono::DateTime;
+use regex::Regex;
+
+const COMMON_PATTERN: &str = r#"^(?:(?P<vhost>\S+) )?(?P<client>\S+) \S+ (?P<user>\S+) \[(?P<date>[^\]]+)\] "(?P<request>(?:[^"\\]|\\.)*)" (?P<status>\d{3}) (?P<bytes>\d+|-)"#;
+const QUOTED_FIELD: &str = r#""((?:[^"\\]|\\.)*)""#;
+const DATE_FORMAT: &str = "%d/%b/%Y:%H:%M:%S %z";
+const LATENCY_KEYS: [&str; 4] = ["rt", "request_time", "urt", "upstream_response_time"];
+
+#[derive(Clone)]
+pub struct AccessLogParser {
+    name: &'static str,
+    description: &'static str,
+    regex: Regex,
+}
+
+impl AccessLogParser {
+    pub fn common() -> Self {
+        let pattern = format!(r#"{COMMON_PATTERN}(?: (?P<extra>[^"].*))?$"#);
+        Self {
+            name: "common",
+            description: "Apache/nginx common log format",
+            regex: Regex::new(&pattern).unwrap(),
+        }
+    }
+
+    pub fn combined() -> Self {
+        let referer = QUOTED_FIELD.replacen('(', "(?P<referer>", 1);
+        let agent = QUOTED_FIELD.replacen('(', "(?P<agent>", 1);
+        let pattern = format!(r#"{COMMON_PATTERN} {referer} {agent}(?: (?P<extra>.*))?$"#);
+        Self {
+            name: "combined",
+            description: "Apache/nginx combined log format",
+            regex: Regex::new(&pattern).unwrap(),
+        }
+    }
+}
+
+impl LogParser for AccessLogParser {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn description(&self) -> &str {
+        self.description
+    }
+
+    fn parse(&mut self, line: &str) -> Option<LogEntry> {
+        let captures = self.regex.captures(line)?;
+        let group = |name: &str| captures.name(name).map(|value| value.as_str());
+        let date = DateTime::parse_from_str(group("date")?, DATE_FORMAT).ok()?;
+        let status = group("status")?;
+        let request = group("request")?;
+
+        let mut fields = Vec::new();
+        let mut push = |key: &str, value: &str| {
+            if value != "-" && !value.is_empty() {
+                fields.push((key.to_string(), value.to_string()));
+            }
+        };
+        let source = match group("vhost") {
+            Some(vhost) => {
+                push("client", group("client")?);
+                vhost
+            }
+            None => group("client")?,
+        };
+        let mut parts = request.split(' ');
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(method), Some(path), protocol, None) => {
+                push("method", method);
+                push("path", path);
+                push("protocol", protocol.unwrap_or_default());
+            }
+            _ => push("request", request),
+        }
+        push("status", status);
+        push("bytes", group("bytes")?);
+        if let Some(latency) = group("extra").and_then(find_latency) {
+            push("latency", latency);
+        }
+        push("user", group("user")?);
+        push("referer", group("referer").unwrap_or_default());
+        push("agent", group("agent").unwrap_or_default());
+
+        Some(LogEntry::from(ExternalLogMessage {
+            message: format!("{status} {request}"),
+            date,
+            source: source.to_string(),
+            level: Some(status_level(status)),
+            fields,
+        }))
+    }
+}
+
+fn status_level(status: &str) -> Level {
+    match status.as_bytes().first() {
+        Some(b'5') => Level::Error,
+        Some(b'4') => Level::Warn,
+        _ => Level::Info,
+    }
+}
+
+/// Latency is not part of either standard format, so it is picked from the
+/// trailing fields: a known `key=value` pair or a bare number such as `%D`.
+fn find_latency(extra: &str) -> Option<&str> {
+    let is_number = |value: &str| !value.is_empty() && value.parse::<f64>().is_ok();
+    let tokens = extra
+        .split_whitespace()
+        .map(|token| token.trim_matches('"'));
+    tokens
+        .clone()
+        .find_map(|token| {
+            let (key, value) = token.split_once('=')?;
+            (LATENCY_KEYS.contains(&key) && is_number(value)).then_some(value)
+        })
+        .or_else(|| tokens.rev().find(|token| is_number(token)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_common_line() {
+        let line =
+            r#"127.0.0.1 - frank [01/Apr/2023:10:00:00 +0200] "GET /index.html HTTP/1.1" 200 2326"#;
+        let entry = AccessLogParser::common().parse(line).unwrap();
+        assert_eq!(entry.message, "200 GET /index.html HTTP/1.1");
+        assert_eq!(entry.source.name, "127.0.0.1");
+        assert_eq!(entry.level, Some(Level::Info));
+        assert_eq!(entry.field("path"), Some("/index.html"));
+        assert_eq!(entry.field("user"), Some("frank"));
+        assert_eq!(
+            entry.date.unwrap().to_rfc3339(),
+            "2023-04-01T10:00:00+02:00"
+        );
+    }
+
+    #[test]
+    fn parses_combined_line_with_latency() {
+        let line = r#"10.0.0.1 - - [01/Apr/2023:10:00:00 +0000] "POST /api HTTP/2.0" 503 0 "-" "curl/8.0" rt=0.250"#;
+        let entry = AccessLogParser::combined().parse(line).unwrap();
+        assert_eq!(entry.level, Some(Level::Error));
+        assert_eq!(entry.field("agent"), Some("curl/8.0"));
+        assert_eq!(entry.field("referer"), None);
+        assert_eq!(entry.field("latency"), Some("0.250"));
+    }
+
+    #[test]
+    fn keeps_malformed_request() {
+        let line = r#"10.0.0.1 - - [01/Apr/2023:10:00:00 +0000] "\x16\x03" 400 0"#;
+        let entry = AccessLogParser::common().parse(line).unwrap();
+        assert_eq!(entry.level, Some(Level::Warn));
+        assert_eq!(entry.field("request"), Some(r"\x16\x03"));
+    }
+}
@@ -0,0 +1,269 @@
+use super::LogParser;
+use crate::config::JsonMapping;
+use crate::file_reader::log_entry::{ExternalLogMessage, Level, LogEntry};
+use crate::file_reader::timestamp::TimestampParser;
+use chrono::{DateTime, FixedOffset};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Clone)]
+pub struct PayloadDecoder {
+    mapping: Option<JsonMapping>,
+    timestamps: TimestampParser,
+}
+
+pub struct DockerParser {
+    decoder: PayloadDecoder,
+    partial_lines: PartialLines,
+}
+
+pub struct CriParser {
+    decoder: PayloadDecoder,
+    partial_lines: PartialLines,
+}
+
+#[derive(Deserialize)]
+struct DockerLine {
+    log: String,
+    stream: String,
+    time: String,
+}
+
+/// Fragments of a line split by the runtime, kept per stream until the final one arrives.
+#[derive(Default)]
+struct PartialLines {
+    fragments: HashMap<String, (DateTime<FixedOffset>, String)>,
+    is_last_partial: bool,
+}
+
+impl PayloadDecoder {
+    pub fn new(mapping: Option<JsonMapping>, timestamps: TimestampParser) -> Self {
+        Self {
+            mapping,
+            timestamps,
+        }
+    }
+
+    fn entry(&self, message: String, date: DateTime<FixedOffset>, stream: &str) -> LogEntry {
+        let payload = self
+            .mapping
+            .as_ref()
+            .filter(|_| message.starts_with('{'))
+            .and_then(|mapping| {
+                let value = serde_json::from_str::<Value>(&message).ok()?;
+                ExternalLogMessage::from_json(&value, mapping, &self.timestamps, Some(date))
+            });
+        let mut payload = payload.unwrap_or_else(|| ExternalLogMessage {
+            level: Level::find_bracketed(&message),
+            message,
+            date,
+            source: "-".to_string(),
+            fields: Vec::new(),
+        });
+        payload
+            .fields
+            .insert(0, ("stream".to_string(), stream.to_string()));
+        LogEntry::from(payload)
+    }
+}
+
+impl PartialLines {
+    fn push(
+        &mut self,
+        stream: &str,
+        date: DateTime<FixedOffset>,
+        fragment: &str,
+        is_partial: bool,
+    ) -> Option<(DateTime<FixedOffset>, String)> {
+        self.is_last_partial = is_partial;
+        let line = match self.fragments.remove(stream) {
+            Some((date, mut line)) => {
+                line.push_str(fragment);
+                (date, line)
+            }
+            None => (date, fragment.to_string()),
+        };
+        if is_partial {
+            self.fragments.insert(stream.to_string(), line);
+            None
+        } else {
+            Some(line)
+        }
+    }
+
+    /// Entries of the lines whose final fragment never arrived, oldest first.
+    fn finish(&mut self, decoder: &PayloadDecoder) -> Vec<LogEntry> {
+        self.is_last_partial = false;
+        let mut lines = self.fragments.drain().collect::<Vec<_>>();
+        lines.sort_by_key(|(_, (date, _))| *date);
+        lines
+            .into_iter()
+            .map(|(stream, (date, message))| decoder.entry(message, date, &stream))
+            .collect()
+    }
+}
+
+impl DockerParser {
+    pub fn new(decoder: PayloadDecoder) -> Self {
+        Self {
+            decoder,
+            partial_lines: PartialLines::default(),
+        }
+    }
+}
+
+impl LogParser for DockerParser {
+    fn name(&self) -> &str {
+        "docker"
+    }
+
+    fn description(&self) -> &str {
+        "docker json-file lines with log, stream and time keys"
+    }
+
+    fn parse(&mut self, line: &str) -> Option<LogEntry> {
+        self.partial_lines.is_last_partial = false;
+        let line = serde_json::from_str::<DockerLine>(line).ok()?;
+        let date = DateTime::parse_from_rfc3339(&line.time).ok()?;
+        let fragment = line.log.strip_suffix('\n');
+        let is_partial = fragment.is_none();
+        let fragment = fragment.unwrap_or(&line.log);
+        let fragment = fragment.strip_suffix('\r').unwrap_or(fragment);
+        let (date, message) = self
+            .partial_lines
+            .push(&line.stream, date, fragment, is_partial)?;
+        Some(self.decoder.entry(message, date, &line.stream))
+    }
+
+    fn finish(&mut self) -> Vec<LogEntry> {
+        self.partial_lines.finish(&self.decoder)
+    }
+
+    fn is_continuation(&self, _line: &str) -> bool {
+        !self.partial_lines.is_last_partial
+    }
+}
+
+impl CriParser {
+    pub fn new(decoder: PayloadDecoder) -> Self {
+        Self {
+            decoder,
+            partial_lines: PartialLines::default(),
+        }
+    }
+}
+
+impl LogParser for CriParser {
+    fn name(&self) -> &str {
+        "cri"
+    }
+
+    fn description(&self) -> &str {
+        "<timestamp> <stream> <P|F> <message> lines written by containerd and CRI-O"
+    }
+
+    fn parse(&mut self, line: &str) -> Option<LogEntry> {
+        self.partial_lines.is_last_partial = false;
+        let mut parts = line.splitn(4, ' ');
+        let (date, stream, tag) = (parts.next()?, parts.next()?, parts.next()?);
+        let fragment = parts.next().unwrap_or_default();
+        let date = DateTime::parse_from_rfc3339(date).ok()?;
+        if !matches!(stream, "stdout" | "stderr") {
+            return None;
+        }
+        let is_partial = match tag.split(':').next()? {
+            "P" => true,
+            "F" => false,
+            _ => return None,
+        };
+        let (date, message) = self
+            .partial_lines
+            .push(stream, date, fragment, is_partial)?;
+        Some(self.decoder.entry(message, date, stream))
+    }
+
+    fn finish(&mut self) -> Vec<LogEntry> {
+        self.partial_lines.finish(&self.decoder)
+    }
+
+    fn is_continuation(&self, _line: &str) -> bool {
+        !self.partial_lines.is_last_partial
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decoder(mapping: Option<JsonMapping>) -> PayloadDecoder {
+        PayloadDecoder::new(mapping, TimestampParser::default())
+    }
+
+    #[test]
+    fn parses_docker_line() {
+        let mut parser = DockerParser::new(decoder(None));
+        let line =
+            r#"{"log":"[ERROR] failed\n","stream":"stderr","time":"2023-04-01T10:00:00.5Z"}"#;
+        let entry = parser.parse(line).unwrap();
+        assert_eq!(entry.message, "[ERROR] failed");
+        assert_eq!(entry.level, Some(Level::Error));
+        assert_eq!(entry.field("stream"), Some("stderr"));
+    }
+
+    #[test]
+    fn joins_docker_partial_lines() {
+        let mut parser = DockerParser::new(decoder(None));
+        let first = r#"{"log":"long ","stream":"stdout","time":"2023-04-01T10:00:00Z"}"#;
+        let last = r#"{"log":"line\n","stream":"stdout","time":"2023-04-01T10:00:01Z"}"#;
+        assert!(parser.parse(first).is_none());
+        assert!(!parser.is_continuation(first));
+        let entry = parser.parse(last).unwrap();
+        assert_eq!(entry.message, "long line");
+        assert_eq!(entry.date_time, "10:00:00.000");
+    }
+
+    #[test]
+    fn joins_cri_partial_lines_per_stream() {
+        let mut parser = CriParser::new(decoder(None));
+        assert!(parser.parse("2023-04-01T10:00:00Z stdout P out ").is_none());
+        let entry = parser.parse("2023-04-01T10:00:00Z stderr F err").unwrap();
+        assert_eq!(entry.message, "err");
+        let entry = parser.parse("2023-04-01T10:00:01Z stdout F line").unwrap();
+        assert_eq!(entry.message, "out line");
+        assert_eq!(entry.field("stream"), Some("stdout"));
+    }
+
+    #[test]
+    fn finishes_with_partial_lines() {
+        let mut parser = DockerParser::new(decoder(None));
+        let line = r#"{"log":"cut ","stream":"stdout","time":"2023-04-01T10:00:00Z"}"#;
+        assert!(parser.parse(line).is_none());
+        let entries = parser.finish();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].message, "cut ");
+        assert!(parser.finish().is_empty());
+
+        let mut parser = CriParser::new(decoder(None));
+        assert!(parser.parse("2023-04-01T10:00:01Z stderr P err").is_none());
+        assert!(parser.parse("2023-04-01T10:00:00Z stdout P out").is_none());
+        let messages = parser.finish().into_iter().map(|entry| entry.message);
+        assert_eq!(messages.collect::<Vec<_>>(), ["out", "err"]);
+    }
+
+    #[test]
+    fn rejects_unknown_cri_stream() {
+        let mut parser = CriParser::new(decoder(None));
+        assert!(parser.parse("2023-04-01T10:00:00Z other F text").is_none());
+    }
+
+    #[test]
+    fn decodes_json_payload() {
+        let mut parser = CriParser::new(decoder(JsonMapping::preset("zap")));
+        let line = r#"2023-04-01T10:00:00Z stdout F {"level":"warn","logger":"db","msg":"slow"}"#;
+        let entry = parser.parse(line).unwrap();
+        assert_eq!(entry.message, "slow");
+        assert_eq!(entry.source.name, "db");
+        assert_eq!(entry.level, Some(Level::Warn));
+    }
+}
@@ -0,0 +1,274 @@
+use super::LogParser;
+use crate::config::CsvMappingConfig;
+use crate::file_reader::log_entry::{ExternalLogMessage, Level, LogEntry};
+use crate::file_reader::timestamp::TimestampParser;
+
+const TIMESTAMP_COLUMNS: [&str; 8] = [
+    "timestamp",
+    "@timestamp",
+    "time",
+    "ts",
+    "date",
+    "datetime",
+    "created_at",
+    "logged_at",
+];
+const MESSAGE_COLUMNS: [&str; 6] = ["message", "msg", "text", "log", "body", "event"];
+const SOURCE_COLUMNS: [&str; 8] = [
+    "source",
+    "service",
+    "logger",
+    "component",
+    "app",
+    "application",
+    "host",
+    "name",
+];
+const LEVEL_COLUMNS: [&str; 6] = [
+    "level",
+    "severity",
+    "log_level",
+    "loglevel",
+    "lvl",
+    "priority",
+];
+
+/// A record with an unclosed quote is shown as unparsed once it grows beyond these.
+const MAX_RECORD_LINES: usize = 100;
+const MAX_RECORD_SIZE: usize = 64 * 1024;
+
+pub struct CsvParser {
+    delimiter: char,
+    mapping: CsvMappingConfig,
+    timestamps: TimestampParser,
+    columns: Option<Columns>,
+    partial_record: Option<String>,
+    is_last_consumed: bool,
+}
+
+struct Columns {
+    names: Vec<String>,
+    timestamp: usize,
+    message: Option<usize>,
+    source: Option<usize>,
+    level: Option<usize>,
+}
+
+impl CsvParser {
+    pub fn new(delimiter: char, mapping: CsvMappingConfig, timestamps: TimestampParser) -> Self {
+        Self {
+            delimiter,
+            mapping,
+            timestamps,
+            columns: None,
+            partial_record: None,
+            is_last_consumed: false,
+        }
+    }
+
+    fn parse_record(
+        &self,
+        columns: &Columns,
+        record: &str,
+        values: Vec<String>,
+    ) -> Option<LogEntry> {
+        if values.len() != columns.names.len() {
+            return None;
+        }
+        let date = self.timestamps.parse(&values[columns.timestamp])?;
+        let mapped = [
+            Some(columns.timestamp),
+            columns.message,
+            columns.source,
+            columns.level,
+        ];
+        let value = |index: Option<usize>| index.map(|index| values[index].clone());
+        let fields = columns
+            .names
+            .iter()
+            .zip(&values)
+            .enumerate()
+            .filter(|(index, (_, value))| !mapped.contains(&Some(*index)) && !value.is_empty())
+            .map(|(_, (name, value))| (name.clone(), value.clone()))
+            .collect();
+        Some(LogEntry::from(ExternalLogMessage {
+            message: value(columns.message).unwrap_or_else(|| record.to_string()),
+            date,
+            source: value(columns.source)
+                .filter(|source| !source.is_empty())
+                .unwrap_or_else(|| "-".to_string()),
+            level: value(columns.level).and_then(|level| Level::parse(&level)),
+            fields,
+        }))
+    }
+}
+
+impl LogParser for CsvParser {
+    fn name(&self) -> &str {
+        match self.delimiter {
+            '\t' => "tsv",
+            _ => "csv",
+        }
+    }
+
+    fn description(&self) -> &str {
+        match self.delimiter {
+            '\t' => "tab separated values with a header row, columns set by --csv-* options",
+            _ => "comma separated values with a header row, columns set by --csv-* options",
+        }
+    }
+
+    fn parse(&mut self, line: &str) -> Option<LogEntry> {
+        self.is_last_consumed = false;
+        let record = match self.partial_record.take() {
+            Some(mut record) => {
+                record.push('\n');
+                record.push_str(line);
+                record
+            }
+            None if line.trim().is_empty() => return None,
+            None => line.to_string(),
+        };
+        let Some(values) = split_record(&record, self.delimiter) else {
+            if record.lines().count() >= MAX_RECORD_LINES || record.len() >= MAX_RECORD_SIZE {
+                return Some(LogEntry::unparsed(&record, None));
+            }
+            self.partial_record = Some(record);
+            self.is_last_consumed = true;
+            return None;
+        };
+        match &self.columns {
+            Some(columns) if values == columns.names => {
+                self.is_last_consumed = true;
+                None
+            }
+            Some(columns) => self.parse_record(columns, &record, values),
+            None => {
+                self.columns = Columns::new(values, &self.mapping);
+                self.is_last_consumed = self.columns.is_some();
+                None
+            }
+        }
+    }
+
+    fn finish(&mut self) -> Vec<LogEntry> {
+        self.partial_record
+            .take()
+            .map(|record| LogEntry::unparsed(&record, None))
+            .into_iter()
+            .collect()
+    }
+
+    fn is_continuation(&self, _line: &str) -> bool {
+        !self.is_last_consumed
+    }
+}
+
+impl Columns {
+    fn new(names: Vec<String>, mapping: &CsvMappingConfig) -> Option<Self> {
+        if names.len() < 2 {
+            return None;
+        }
+        let find = |configured: &Option<String>, candidates: &[&str]| match configured {
+            Some(name) => names.iter().position(|column| column == name),
+            None => candidates.iter().find_map(|candidate| {
+                names
+                    .iter()
+                    .position(|column| column.trim().eq_ignore_ascii_case(candidate))
+            }),
+        };
+        Some(Self {
+            timestamp: find(&mapping.timestamp, &TIMESTAMP_COLUMNS)?,
+            message: find(&mapping.message, &MESSAGE_COLUMNS),
+            source: find(&mapping.source, &SOURCE_COLUMNS),
+            level: find(&mapping.level, &LEVEL_COLUMNS),
+            names,
+        })
+    }
+}
+
+/// Returns `None` while a quoted cell is still open, e.g. it continues on the next line.
+fn split_record(record: &str, delimiter: char) -> Option<Vec<String>> {
+    let mut values = Vec::new();
+    let mut value = String::new();
+    let mut is_quoted = false;
+    let mut chars = record.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if is_quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                value.push('"');
+            }
+            '"' if is_quoted => is_quoted = false,
+            '"' if value.is_empty() => is_quoted = true,
+            c if c == delimiter && !is_quoted => values.push(std::mem::take(&mut value)),
+            c => value.push(c),
+        }
+    }
+    if is_quoted {
+        return None;
+    }
+    values.push(value);
+    Some(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "timestamp,level,message";
+
+    fn parser() -> CsvParser {
+        CsvParser::new(',', CsvMappingConfig::default(), TimestampParser::default())
+    }
+
+    #[test]
+    fn parses_quoted_multi_line_records() {
+        let mut parser = parser();
+        assert!(parser.parse(HEADER).is_none());
+        assert!(parser
+            .parse("2023-04-01T10:00:00Z,WARN,\"slow \"\"query\"\"")
+            .is_none());
+        assert!(!parser.is_continuation("next"));
+        let entry = parser.parse("took 5s\"").unwrap();
+        assert_eq!(entry.message, "slow \"query\"\ntook 5s");
+        assert_eq!(entry.level, Some(Level::Warn));
+    }
+
+    #[test]
+    fn skips_repeated_header() {
+        let mut parser = parser();
+        assert!(parser.parse(HEADER).is_none());
+        assert!(parser.parse(HEADER).is_none());
+        assert!(!parser.is_continuation(HEADER));
+        assert!(parser.parse("2023-04-01T10:00:00Z,INFO,started").is_some());
+    }
+
+    #[test]
+    fn emits_unclosed_record_as_unparsed() {
+        let mut parser = parser();
+        assert!(parser.parse(HEADER).is_none());
+        assert!(parser
+            .parse("2023-04-01T10:00:00Z,INFO,\"unclosed")
+            .is_none());
+        let entry = (1..MAX_RECORD_LINES)
+            .find_map(|_| parser.parse("text"))
+            .unwrap();
+        assert!(entry.is_unparsed());
+        assert_eq!(entry.lines_count, MAX_RECORD_LINES);
+        assert!(parser.parse("2023-04-01T10:00:01Z,INFO,started").is_some());
+    }
+
+    #[test]
+    fn finishes_with_unclosed_record() {
+        let mut parser = parser();
+        assert!(parser.parse(HEADER).is_none());
+        assert!(parser
+            .parse("2023-04-01T10:00:00Z,INFO,\"unclosed")
+            .is_none());
+        let entries = parser.finish();
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].is_unparsed());
+        assert!(parser.finish().is_empty());
+    }
+}
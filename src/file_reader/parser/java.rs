@@ -0,0 +1,153 @@
+use super::LogParser;
+use crate::file_reader::log_entry::{ExternalLogMessage, Level, LogEntry};
+use crate::file_reader::timestamp::{today_at, TimestampParser};
+use chrono::NaiveTime;
+use regex::Regex;
+
+const DATE: &str =
+    r"(?P<date>(?:\d{4}-\d{2}-\d{2}[ T])?\d{2}:\d{2}:\d{2}(?:[.,]\d{1,9})?(?:Z|[+-]\d{2}:?\d{2})?)";
+const PATTERNS: [&str; 3] = [
+    // logback and log4j2 defaults: date [thread] LEVEL logger - message
+    r"^{date}\s+\[(?P<thread>[^\]]+)\]\s+(?P<level>[A-Z]+)\s+(?P<logger>\S+?)\s*[-:] (?P<message>.*)$",
+    // log4j: date LEVEL [thread] logger - message
+    r"^{date}\s+(?P<level>[A-Z]+)\s+\[(?P<thread>[^\]]+)\]\s+(?P<logger>\S+?)\s*[-:] (?P<message>.*)$",
+    // spring boot: date LEVEL pid --- [thread] logger : message
+    r"^{date}\s+(?P<level>[A-Z]+)\s+(?P<pid>\d+)\s+---\s+\[\s*(?P<thread>[^\]]+?)\]\s+(?P<logger>\S+)\s+: (?P<message>.*)$",
+];
+
+#[derive(Clone)]
+pub struct JavaParser {
+    patterns: Vec<Regex>,
+    timestamps: TimestampParser,
+}
+
+impl JavaParser {
+    pub fn new(timestamps: TimestampParser) -> Self {
+        let patterns = PATTERNS
+            .iter()
+            .map(|pattern| Regex::new(&pattern.replace("{date}", DATE)).unwrap())
+            .collect();
+        Self {
+            patterns,
+            timestamps,
+        }
+    }
+}
+
+impl LogParser for JavaParser {
+    fn name(&self) -> &str {
+        "java"
+    }
+
+    fn description(&self) -> &str {
+        "logback/log4j <date> [<thread>] <LEVEL> <logger> - <message> lines with stack traces"
+    }
+
+    fn parse(&mut self, line: &str) -> Option<LogEntry> {
+        let captures = self
+            .patterns
+            .iter()
+            .find_map(|pattern| pattern.captures(line))?;
+        let date = captures.name("date")?.as_str().replace(',', ".");
+        let date = match NaiveTime::parse_from_str(&date, "%H:%M:%S%.f") {
+            Ok(time) => today_at(time)?,
+            Err(_) => self.timestamps.parse(&date)?,
+        };
+        let fields = ["thread", "pid"]
+            .into_iter()
+            .filter_map(|key| {
+                let value = captures.name(key)?;
+                Some((key.to_string(), value.as_str().to_string()))
+            })
+            .collect();
+        Some(LogEntry::from(ExternalLogMessage {
+            message: captures.name("message")?.as_str().to_string(),
+            date,
+            source: captures.name("logger")?.as_str().to_string(),
+            level: Level::parse(captures.name("level")?.as_str()),
+            fields,
+        }))
+    }
+
+    fn append(&mut self, entry: &mut LogEntry, line: &str) {
+        if !entry.stack_trace.is_empty() || is_stack_trace_line(line) {
+            entry.push_stack_trace(line);
+        } else {
+            entry.append(line);
+        }
+    }
+}
+
+fn is_stack_trace_line(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    let is_indented = trimmed.len() < line.len();
+    (is_indented && (trimmed.starts_with("at ") || trimmed.starts_with("...")))
+        || trimmed.starts_with("Caused by: ")
+        || trimmed.starts_with("Suppressed: ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, NaiveDate, TimeZone};
+
+    const LINE: &str = "2023-04-01 10:00:00,123 [main] ERROR com.example.Service - request failed";
+
+    fn parse_with(lines: &[&str]) -> LogEntry {
+        let mut parser = JavaParser::new(TimestampParser::default());
+        let mut entry = parser.parse(LINE).unwrap();
+        for line in lines {
+            assert!(parser.parse(line).is_none());
+            parser.append(&mut entry, line);
+        }
+        entry
+    }
+
+    #[test]
+    fn parses_logback_line() {
+        let entry = parse_with(&[]);
+        assert_eq!(entry.message, "request failed");
+        assert_eq!(entry.source.name, "com.example.Service");
+        assert_eq!(entry.level, Some(Level::Error));
+        assert_eq!(entry.field("thread"), Some("main"));
+    }
+
+    #[test]
+    fn parses_date_without_offset_as_local_time() {
+        let entry = parse_with(&[]);
+        let expected = NaiveDate::from_ymd_opt(2023, 4, 1)
+            .and_then(|date| date.and_hms_milli_opt(10, 0, 0, 123))
+            .and_then(|date| Local.from_local_datetime(&date).earliest())
+            .unwrap();
+        assert_eq!(entry.date, Some(expected.into()));
+        assert_eq!(entry.date_time, "10:00:00.123");
+    }
+
+    #[test]
+    fn collects_stack_trace_after_exception_header() {
+        let entry = parse_with(&[
+            "java.lang.IllegalStateException: Connection closed",
+            "\tat com.example.Client.send(Client.java:42)",
+            "Caused by: java.io.IOException: Broken pipe",
+        ]);
+        assert_eq!(entry.message, "request failed");
+        assert_eq!(entry.stack_trace.len(), 3);
+        assert_eq!(entry.exception_name(), Some("IllegalStateException"));
+        assert!(entry.matches_query("connection closed"));
+        assert!(entry.matches_query("client.java:42"));
+        assert!(entry.matches_query("broken pipe"));
+    }
+
+    #[test]
+    fn has_no_exception_name_without_header() {
+        let entry = parse_with(&["\tat com.example.Client.send(Client.java:42)"]);
+        assert_eq!(entry.stack_trace.len(), 1);
+        assert_eq!(entry.exception_name(), None);
+    }
+
+    #[test]
+    fn searches_appended_lines() {
+        let entry = parse_with(&["Retrying in 5 seconds"]);
+        assert!(entry.matches_query("retrying"));
+    }
+}
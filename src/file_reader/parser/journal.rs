@@ -0,0 +1,241 @@
+use super::LogParser;
+use crate::file_reader::log_entry::{ExternalLogMessage, Level, LogEntry};
+use chrono::{TimeZone, Utc};
+use serde_json::Value;
+
+const SOURCE_FIELDS: [&str; 2] = ["SYSLOG_IDENTIFIER", "_SYSTEMD_UNIT"];
+const LENGTH_SIZE: usize = 8;
+/// Larger lengths mean the bare line was not a binary field, e.g. a stray `OK`.
+const MAX_BINARY_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Journal export format: `KEY=value` lines and an empty line after each entry.
+/// Values with line breaks or binary data are written as the bare key, followed by
+/// a little-endian 64-bit length, the raw value and a line break.
+#[derive(Default)]
+pub struct JournalExportParser {
+    fields: Vec<(String, String)>,
+    binary_field: Option<(String, Vec<u8>)>,
+    is_last_consumed: bool,
+}
+
+pub struct JournalJsonParser;
+
+enum BinaryValue {
+    Incomplete,
+    Complete(String),
+    Invalid,
+}
+
+impl LogParser for JournalExportParser {
+    fn name(&self) -> &str {
+        "journal"
+    }
+
+    fn description(&self) -> &str {
+        "systemd journal export format of journalctl -o export"
+    }
+
+    fn parse(&mut self, line: &str) -> Option<LogEntry> {
+        self.parse_all(line).into_iter().next()
+    }
+
+    fn parse_all(&mut self, line: &str) -> Vec<LogEntry> {
+        self.parse_bytes(format!("{line}\n").as_bytes())
+    }
+
+    fn parse_bytes(&mut self, line: &[u8]) -> Vec<LogEntry> {
+        self.is_last_consumed = true;
+        if let Some((key, mut data)) = self.binary_field.take() {
+            data.extend_from_slice(line);
+            match binary_value(&data) {
+                BinaryValue::Incomplete => self.binary_field = Some((key, data)),
+                BinaryValue::Complete(value) => self.fields.push((key, value)),
+                BinaryValue::Invalid => {
+                    self.fields.clear();
+                    self.is_last_consumed = false;
+                }
+            }
+            return Vec::new();
+        }
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        if line.is_empty() {
+            let fields = std::mem::take(&mut self.fields);
+            return entry_from_fields(fields).into_iter().collect();
+        }
+        match line.iter().position(|byte| *byte == b'=') {
+            Some(index) if is_field_name(&line[..index]) => {
+                let key = String::from_utf8_lossy(&line[..index]).into_owned();
+                let value = String::from_utf8_lossy(&line[index + 1..]).into_owned();
+                self.fields.push((key, value));
+            }
+            None if is_field_name(line) => {
+                let key = String::from_utf8_lossy(line).into_owned();
+                self.binary_field = Some((key, Vec::new()));
+            }
+            _ => self.is_last_consumed = false,
+        }
+        Vec::new()
+    }
+
+    fn is_continuation(&self, _line: &str) -> bool {
+        !self.is_last_consumed
+    }
+}
+
+impl LogParser for JournalJsonParser {
+    fn name(&self) -> &str {
+        "journal-json"
+    }
+
+    fn description(&self) -> &str {
+        "systemd journal entries of journalctl -o json, one object per line"
+    }
+
+    fn parse(&mut self, line: &str) -> Option<LogEntry> {
+        let Ok(Value::Object(object)) = serde_json::from_str::<Value>(line) else {
+            return None;
+        };
+        let mut fields = Vec::new();
+        for (key, value) in object {
+            match value {
+                Value::Array(values) if byte_array(&values).is_none() => {
+                    for value in &values {
+                        fields.extend(json_field_value(value).map(|value| (key.clone(), value)));
+                    }
+                }
+                value => fields.extend(json_field_value(&value).map(|value| (key, value))),
+            }
+        }
+        entry_from_fields(fields)
+    }
+}
+
+fn entry_from_fields(mut fields: Vec<(String, String)>) -> Option<LogEntry> {
+    let micros = take_field(&mut fields, "__REALTIME_TIMESTAMP")?
+        .parse::<i64>()
+        .ok()?;
+    let message = take_field(&mut fields, "MESSAGE").unwrap_or_default();
+    let level = take_field(&mut fields, "PRIORITY")
+        .and_then(|priority| priority.parse().ok())
+        .and_then(Level::from_syslog_severity);
+    let source = SOURCE_FIELDS
+        .into_iter()
+        .find_map(|key| take_field(&mut fields, key))
+        .unwrap_or_else(|| "-".to_string());
+    Some(LogEntry::from(ExternalLogMessage {
+        message,
+        date: Utc.timestamp_nanos(micros.checked_mul(1_000)?).into(),
+        source,
+        level,
+        fields,
+    }))
+}
+
+fn take_field(fields: &mut Vec<(String, String)>, key: &str) -> Option<String> {
+    let index = fields.iter().position(|(name, _)| name == key)?;
+    Some(fields.remove(index).1)
+}
+
+/// Journal field names are upper case letters, digits and underscores.
+fn is_field_name(name: &[u8]) -> bool {
+    !name.is_empty()
+        && !name[0].is_ascii_digit()
+        && name
+            .iter()
+            .all(|byte| byte.is_ascii_uppercase() || byte.is_ascii_digit() || *byte == b'_')
+}
+
+/// The value is incomplete until the length, the value and its trailing line
+/// break are read. An implausible length or a missing line break is invalid.
+fn binary_value(data: &[u8]) -> BinaryValue {
+    let Some(length) = data.get(..LENGTH_SIZE) else {
+        return BinaryValue::Incomplete;
+    };
+    let length = u64::from_le_bytes(length.try_into().unwrap());
+    if length > MAX_BINARY_SIZE {
+        return BinaryValue::Invalid;
+    }
+    let end = LENGTH_SIZE + length as usize;
+    match data.get(end..) {
+        None | Some([]) => BinaryValue::Incomplete,
+        Some(b"\n") => {
+            BinaryValue::Complete(String::from_utf8_lossy(&data[LENGTH_SIZE..end]).into_owned())
+        }
+        Some(_) => BinaryValue::Invalid,
+    }
+}
+
+/// journalctl writes values that are not valid UTF-8 as arrays of bytes.
+fn byte_array(values: &[Value]) -> Option<Vec<u8>> {
+    values
+        .iter()
+        .map(|value| value.as_u64().and_then(|byte| u8::try_from(byte).ok()))
+        .collect()
+}
+
+fn json_field_value(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(value) => Some(value.clone()),
+        Value::Array(values) => {
+            byte_array(values).map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+        }
+        value => Some(value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(parser: &mut JournalExportParser, data: &[u8]) -> Vec<LogEntry> {
+        data.split_inclusive(|byte| *byte == b'\n')
+            .flat_map(|line| parser.parse_bytes(line))
+            .collect()
+    }
+
+    #[test]
+    fn parses_text_and_binary_fields() {
+        let mut data = b"__REALTIME_TIMESTAMP=1680343200000000\nPRIORITY=3\nMESSAGE\n".to_vec();
+        data.extend_from_slice(&12u64.to_le_bytes());
+        data.extend_from_slice(b"first\nsecond\n\n");
+        let entries = parse(&mut JournalExportParser::default(), &data);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].message, "first\nsecond");
+        assert_eq!(entries[0].level, Some(Level::Error));
+    }
+
+    #[test]
+    fn rejects_bare_words_without_length() {
+        let mut parser = JournalExportParser::default();
+        assert!(parser.parse_bytes(b"OK\n").is_empty());
+        assert!(parser.parse_bytes(b"MESSAGE=started\n").is_empty());
+        assert!(parser.is_continuation("MESSAGE=started"));
+        let data = b"MESSAGE=next\n__REALTIME_TIMESTAMP=1680343200000000\n\n";
+        let entries = parse(&mut parser, data);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].message, "next");
+    }
+
+    #[test]
+    fn rejects_value_without_trailing_line_break() {
+        let mut parser = JournalExportParser::default();
+        let mut data = b"MESSAGE\n".to_vec();
+        data.extend_from_slice(&2u64.to_le_bytes());
+        data.extend_from_slice(b"abc\n");
+        assert!(parse(&mut parser, &data).is_empty());
+        assert!(parser.is_continuation("abc"));
+        assert!(parser.binary_field.is_none());
+    }
+
+    #[test]
+    fn rejects_implausible_lengths() {
+        let mut parser = JournalExportParser::default();
+        let mut data = b"MESSAGE\n".to_vec();
+        data.extend_from_slice(&u64::MAX.to_le_bytes());
+        data.push(b'\n');
+        assert!(parse(&mut parser, &data).is_empty());
+        assert!(parser.is_continuation(""));
+        assert!(parser.binary_field.is_none());
+    }
+}
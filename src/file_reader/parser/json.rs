@@ -0,0 +1,104 @@
+use super::LogParser;
+use crate::config::JsonMapping;
+use crate::file_reader::log_entry::LogEntry;
+use crate::file_reader::timestamp::TimestampParser;
+
+pub struct JsonParser {
+    name: &'static str,
+    description: &'static str,
+    mapping: JsonMapping,
+    timestamps: TimestampParser,
+}
+
+impl JsonParser {
+    pub fn new(mapping: JsonMapping, timestamps: TimestampParser) -> Self {
+        Self {
+            name: "json",
+            description: "one JSON object per line, keys are configured with --json-* options",
+            mapping,
+            timestamps,
+        }
+    }
+
+    pub fn ecs(timestamps: TimestampParser) -> Self {
+        Self {
+            name: "ecs",
+            description: "Elastic Common Schema JSON lines",
+            mapping: JsonMapping::preset("ecs").unwrap(),
+            timestamps,
+        }
+    }
+
+    pub fn gelf(timestamps: TimestampParser) -> Self {
+        Self {
+            name: "gelf",
+            description: "Graylog Extended Log Format JSON lines",
+            mapping: JsonMapping::preset("gelf").unwrap(),
+            timestamps,
+        }
+    }
+}
+
+impl LogParser for JsonParser {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn description(&self) -> &str {
+        self.description
+    }
+
+    fn parse(&mut self, line: &str) -> Option<LogEntry> {
+        LogEntry::from_json(line, &self.mapping, &self.timestamps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_reader::log_entry::Level;
+
+    #[test]
+    fn parses_configured_keys() {
+        let mapping = JsonMapping::preset("bunyan").unwrap();
+        let mut parser = JsonParser::new(mapping, TimestampParser::default());
+        let line =
+            r#"{"time":"2023-04-01T10:00:00Z","name":"api","level":50,"msg":"failed","req_id":7}"#;
+        let entry = parser.parse(line).unwrap();
+        assert_eq!(entry.message, "failed");
+        assert_eq!(entry.source.name, "api");
+        assert_eq!(entry.level, Some(Level::Error));
+        assert_eq!(entry.field("req_id"), Some("7"));
+    }
+
+    #[test]
+    fn parses_nested_ecs_keys() {
+        let line = r#"{"@timestamp":"2023-04-01T10:00:00Z","log":{"level":"warn"},"service":{"name":"api"},"message":"slow"}"#;
+        let entry = JsonParser::ecs(TimestampParser::default())
+            .parse(line)
+            .unwrap();
+        assert_eq!(entry.message, "slow");
+        assert_eq!(entry.source.name, "api");
+        assert_eq!(entry.level, Some(Level::Warn));
+    }
+
+    #[test]
+    fn parses_gelf_epoch_and_severity() {
+        let line = r#"{"version":"1.1","host":"web1","short_message":"started","timestamp":1680343200.5,"level":6}"#;
+        let entry = JsonParser::gelf(TimestampParser::default())
+            .parse(line)
+            .unwrap();
+        assert_eq!(entry.message, "started");
+        assert_eq!(entry.source.name, "web1");
+        assert_eq!(entry.level, Some(Level::Info));
+        assert_eq!(entry.date.unwrap().timestamp_millis(), 1_680_343_200_500);
+    }
+
+    #[test]
+    fn rejects_lines_without_timestamp() {
+        let mapping = JsonMapping::preset("tuna").unwrap();
+        let mut parser = JsonParser::new(mapping, TimestampParser::default());
+        assert!(parser.parse(r#"{"message":"no date"}"#).is_none());
+        assert!(parser.parse("not json").is_none());
+    }
+}
@@ -0,0 +1,75 @@
+use super::LogParser;
+use crate::file_reader::log_entry::{ExternalLogMessage, Level, LogEntry};
+use crate::file_reader::timestamp::with_inferred_year;
+
+const BUFFER_HEADER: &str = "--------- ";
+
+pub struct LogcatParser;
+
+impl LogParser for LogcatParser {
+    fn name(&self) -> &str {
+        "logcat"
+    }
+
+    fn description(&self) -> &str {
+        "<MM-DD> <time> <pid> <tid> <priority> <tag>: <message> lines of adb logcat -v threadtime"
+    }
+
+    fn parse(&mut self, line: &str) -> Option<LogEntry> {
+        let mut parts = line.split_whitespace();
+        let (day, time) = (parts.next()?, parts.next()?);
+        let (pid, tid, priority) = (parts.next()?, parts.next()?, parts.next()?);
+        let is_id = |value: &str| value.chars().all(|c| c.is_ascii_digit());
+        if !is_id(pid) || !is_id(tid) {
+            return None;
+        }
+        let level = Level::from_logcat_priority(priority)?;
+        let date = with_inferred_year(&format!("{day} {time}"), "%m-%d %H:%M:%S%.3f")?;
+        let (_, rest) = line.split_once(&format!(" {priority} "))?;
+        let (tag, message) = rest.split_once(':')?;
+        Some(LogEntry::from(ExternalLogMessage {
+            message: message.strip_prefix(' ').unwrap_or(message).to_string(),
+            date,
+            source: tag.trim().to_string(),
+            level: Some(level),
+            fields: vec![
+                ("pid".to_string(), pid.to_string()),
+                ("tid".to_string(), tid.to_string()),
+            ],
+        }))
+    }
+
+    fn is_continuation(&self, line: &str) -> bool {
+        !line.starts_with(BUFFER_HEADER)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_threadtime_line() {
+        let line = "04-01 10:00:00.123  1234  5678 W ActivityManager: Slow operation";
+        let entry = LogcatParser.parse(line).unwrap();
+        assert_eq!(entry.message, "Slow operation");
+        assert_eq!(entry.source.name, "ActivityManager");
+        assert_eq!(entry.level, Some(Level::Warn));
+        assert_eq!(entry.field("pid"), Some("1234"));
+        assert_eq!(entry.field("tid"), Some("5678"));
+        assert_eq!(entry.date_time, "10:00:00.123");
+    }
+
+    #[test]
+    fn consumes_buffer_headers() {
+        let line = "--------- beginning of main";
+        assert!(LogcatParser.parse(line).is_none());
+        assert!(!LogcatParser.is_continuation(line));
+    }
+
+    #[test]
+    fn rejects_non_numeric_ids() {
+        let line = "04-01 10:00:00.123  app  5678 W Tag: message";
+        assert!(LogcatParser.parse(line).is_none());
+    }
+}
@@ -0,0 +1,65 @@
+use super::LogParser;
+use crate::file_reader::log_entry::LogEntry;
+use crate::file_reader::timestamp::TimestampParser;
+
+pub struct LogfmtParser {
+    source_key: String,
+    timestamps: TimestampParser,
+}
+
+impl LogfmtParser {
+    pub fn new(source_key: String, timestamps: TimestampParser) -> Self {
+        Self {
+            source_key,
+            timestamps,
+        }
+    }
+}
+
+impl LogParser for LogfmtParser {
+    fn name(&self) -> &str {
+        "logfmt"
+    }
+
+    fn description(&self) -> &str {
+        "key=value pairs with time/ts, level and msg keys"
+    }
+
+    fn parse(&mut self, line: &str) -> Option<LogEntry> {
+        LogEntry::from_logfmt(line, &self.source_key, &self.timestamps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_reader::log_entry::Level;
+
+    #[test]
+    fn parses_known_keys_and_fields() {
+        let mut parser = LogfmtParser::new("service".to_string(), TimestampParser::default());
+        let line = r#"time=2023-04-01T10:00:00Z level=warn service=api msg="slow query" took=5s"#;
+        let entry = parser.parse(line).unwrap();
+        assert_eq!(entry.message, "slow query");
+        assert_eq!(entry.source.name, "api");
+        assert_eq!(entry.level, Some(Level::Warn));
+        assert_eq!(entry.fields, [("took".to_string(), "5s".to_string())]);
+    }
+
+    #[test]
+    fn keeps_unknown_level_as_field() {
+        let mut parser = LogfmtParser::new("service".to_string(), TimestampParser::default());
+        let entry = parser
+            .parse("ts=2023-04-01T10:00:00Z level=loud msg=x")
+            .unwrap();
+        assert_eq!(entry.level, None);
+        assert_eq!(entry.field("level"), Some("loud"));
+    }
+
+    #[test]
+    fn rejects_lines_without_timestamp() {
+        let mut parser = LogfmtParser::new("service".to_string(), TimestampParser::default());
+        assert!(parser.parse("level=info msg=started").is_none());
+        assert!(parser.parse("plain text").is_none());
+    }
+}
@@ -0,0 +1,179 @@
+use super::log_entry::LogEntry;
+use super::timestamp::TimestampParser;
+use crate::config::Config;
+
+mod access_log;
+mod container;
+mod csv;
+mod java;
+mod journal;
+mod json;
+mod logcat;
+mod logfmt;
+mod otlp;
+mod plain;
+mod python;
+mod raw;
+mod regex_format;
+mod rust_log;
+mod syslog;
+
+pub use access_log::AccessLogParser;
+pub use container::{CriParser, DockerParser, PayloadDecoder};
+pub use csv::CsvParser;
+pub use java::JavaParser;
+pub use journal::{JournalExportParser, JournalJsonParser};
+pub use json::JsonParser;
+pub use logcat::LogcatParser;
+pub use logfmt::LogfmtParser;
+pub use otlp::OtlpParser;
+pub use plain::PlainTextParser;
+pub use python::PythonParser;
+pub use raw::RawParser;
+pub use regex_format::RegexParser;
+pub use rust_log::{EnvLoggerParser, TracingParser, TunaParser};
+pub use syslog::{Rfc3164Parser, Rfc5424Parser};
+
+pub trait LogParser: Send {
+    fn name(&self) -> &str;
+    fn description(&self) -> &str;
+    fn parse(&mut self, line: &str) -> Option<LogEntry>;
+
+    /// Formats that export several records per line, like OTLP batches, override this.
+    fn parse_all(&mut self, line: &str) -> Vec<LogEntry> {
+        self.parse(line).into_iter().collect()
+    }
+
+    /// Receives the raw line with its line break, binary-safe formats like the
+    /// journal export format override this.
+    fn parse_bytes(&mut self, line: &[u8]) -> Vec<LogEntry> {
+        let line = String::from_utf8_lossy(line);
+        self.parse_all(line.trim_end_matches(['\n', '\r']))
+    }
+
+    /// Called at the end of the input for records that are still incomplete.
+    fn finish(&mut self) -> Vec<LogEntry> {
+        Vec::new()
+    }
+
+    fn is_continuation(&self, _line: &str) -> bool {
+        true
+    }
+
+    fn append(&mut self, entry: &mut LogEntry, line: &str) {
+        entry.append(line);
+    }
+}
+
+type ParserFactory = Box<dyn Fn() -> Box<dyn LogParser>>;
+
+pub struct ParserRegistry {
+    factories: Vec<ParserFactory>,
+}
+
+impl ParserRegistry {
+    pub fn new(config: &Config) -> Self {
+        let mut registry = Self {
+            factories: Vec::new(),
+        };
+        let timestamps = TimestampParser::new(&config.timestamp_formats);
+        for format in &config.formats {
+            let Ok(regex) = format.compile() else {
+                continue;
+            };
+            let format = format.clone();
+            let date_formats = format
+                .date_format
+                .iter()
+                .chain(&config.timestamp_formats)
+                .cloned()
+                .collect::<Vec<_>>();
+            let timestamps = TimestampParser::new(&date_formats);
+            registry.register(move || {
+                Box::new(RegexParser::new(&format, regex.clone(), timestamps.clone()))
+            });
+        }
+        let json_mapping = config.json.mapping();
+        let json_timestamps = timestamps.clone();
+        registry.register(move || {
+            Box::new(JsonParser::new(
+                json_mapping.clone(),
+                json_timestamps.clone(),
+            ))
+        });
+        let ecs_timestamps = timestamps.clone();
+        registry.register(move || Box::new(JsonParser::ecs(ecs_timestamps.clone())));
+        let gelf_timestamps = timestamps.clone();
+        registry.register(move || Box::new(JsonParser::gelf(gelf_timestamps.clone())));
+        registry.register(|| Box::new(OtlpParser));
+        registry.register(|| Box::new(JournalJsonParser));
+        registry.register(|| Box::<JournalExportParser>::default());
+        let logfmt_source = config.logfmt.source_key();
+        let logfmt_timestamps = timestamps.clone();
+        registry.register(move || {
+            Box::new(LogfmtParser::new(
+                logfmt_source.clone(),
+                logfmt_timestamps.clone(),
+            ))
+        });
+        let payload_decoder = PayloadDecoder::new(
+            config.container.decode_json.then(|| config.json.mapping()),
+            timestamps.clone(),
+        );
+        let docker_decoder = payload_decoder.clone();
+        registry.register(move || Box::new(DockerParser::new(docker_decoder.clone())));
+        registry.register(move || Box::new(CriParser::new(payload_decoder.clone())));
+        let java = JavaParser::new(timestamps.clone());
+        registry.register(move || Box::new(java.clone()));
+        let python_timestamps = timestamps.clone();
+        registry.register(move || Box::new(PythonParser::new(python_timestamps.clone())));
+        registry.register(|| Box::new(LogcatParser));
+        for delimiter in [',', '\t'] {
+            let mapping = config.csv.clone();
+            let timestamps = timestamps.clone();
+            registry.register(move || {
+                Box::new(CsvParser::new(
+                    delimiter,
+                    mapping.clone(),
+                    timestamps.clone(),
+                ))
+            });
+        }
+        let common = AccessLogParser::common();
+        registry.register(move || Box::new(common.clone()));
+        let combined = AccessLogParser::combined();
+        registry.register(move || Box::new(combined.clone()));
+        registry.register(|| Box::new(EnvLoggerParser));
+        registry.register(|| Box::new(TracingParser));
+        registry.register(|| Box::new(TunaParser));
+        registry.register(|| Box::new(Rfc5424Parser));
+        registry.register(|| Box::new(Rfc3164Parser));
+        registry.register(move || Box::new(RawParser::new(timestamps.clone())));
+        registry
+    }
+
+    pub fn register<F>(&mut self, factory: F)
+    where
+        F: Fn() -> Box<dyn LogParser> + 'static,
+    {
+        self.factories.push(Box::new(factory));
+    }
+
+    pub fn create(&self, name: &str) -> Option<Box<dyn LogParser>> {
+        self.formats().find(|parser| parser.name() == name)
+    }
+
+    /// Parsers taking part in format detection.
+    pub fn parsers(&self) -> impl Iterator<Item = Box<dyn LogParser>> + '_ {
+        self.factories.iter().map(|factory| factory())
+    }
+
+    /// Used when no format is detected, it accepts any line.
+    pub fn fallback(&self) -> Box<dyn LogParser> {
+        Box::new(PlainTextParser)
+    }
+
+    pub fn formats(&self) -> impl Iterator<Item = Box<dyn LogParser>> + '_ {
+        self.parsers().chain(std::iter::once(self.fallback()))
+    }
+}
@@ -0,0 +1,160 @@
+use super::LogParser;
+use crate::file_reader::log_entry::{ExternalLogMessage, Level, LogEntry};
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+use serde_json::Value;
+
+pub struct OtlpParser;
+
+impl LogParser for OtlpParser {
+    fn name(&self) -> &str {
+        "otlp"
+    }
+
+    fn description(&self) -> &str {
+        "OpenTelemetry OTLP JSON exports, one resourceLogs batch per line"
+    }
+
+    fn parse(&mut self, line: &str) -> Option<LogEntry> {
+        self.parse_all(line).into_iter().next()
+    }
+
+    fn parse_all(&mut self, line: &str) -> Vec<LogEntry> {
+        let Ok(value) = serde_json::from_str::<Value>(line) else {
+            return Vec::new();
+        };
+        let mut entries = Vec::new();
+        for resource_logs in array(&value, "resourceLogs") {
+            let resource_fields = attributes(&resource_logs["resource"]);
+            for scope_logs in array(resource_logs, "scopeLogs") {
+                let scope = scope_logs["scope"]["name"]
+                    .as_str()
+                    .filter(|s| !s.is_empty());
+                for record in array(scope_logs, "logRecords") {
+                    let entry = parse_record(record, &resource_fields, scope);
+                    entries.extend(entry.map(LogEntry::from));
+                }
+            }
+        }
+        entries
+    }
+}
+
+fn parse_record(
+    record: &Value,
+    resource_fields: &[(String, String)],
+    scope: Option<&str>,
+) -> Option<ExternalLogMessage> {
+    let date = ["timeUnixNano", "observedTimeUnixNano"]
+        .into_iter()
+        .find_map(|key| unix_nanos(&record[key]))?;
+    let level = record["severityText"]
+        .as_str()
+        .and_then(Level::parse)
+        .or_else(|| {
+            record["severityNumber"]
+                .as_i64()
+                .and_then(Level::from_otel_severity)
+        });
+    let mut fields = resource_fields.to_vec();
+    let source = fields
+        .iter()
+        .find(|(key, _)| key == "service.name")
+        .map(|(_, value)| value.as_str())
+        .or(scope)
+        .unwrap_or("-")
+        .to_string();
+    if let Some(scope) = scope {
+        fields.push(("scope".to_string(), scope.to_string()));
+    }
+    fields.extend(attributes(record));
+    for (key, field) in [("traceId", "trace_id"), ("spanId", "span_id")] {
+        if let Some(id) = record[key].as_str().filter(|id| !id.is_empty()) {
+            fields.push((field.to_string(), id.to_string()));
+        }
+    }
+    Some(ExternalLogMessage {
+        message: any_value(&record["body"]),
+        date,
+        source,
+        level,
+        fields,
+    })
+}
+
+fn array<'v>(value: &'v Value, key: &str) -> impl Iterator<Item = &'v Value> {
+    value[key].as_array().into_iter().flatten()
+}
+
+fn attributes(value: &Value) -> Vec<(String, String)> {
+    array(value, "attributes")
+        .filter_map(|attribute| {
+            let key = attribute["key"].as_str()?;
+            Some((key.to_string(), any_value(&attribute["value"])))
+        })
+        .collect()
+}
+
+/// Converts an OTLP `AnyValue`, where 64-bit integers are encoded as strings.
+fn any_value(value: &Value) -> String {
+    let Some((kind, value)) = value.as_object().and_then(|object| object.iter().next()) else {
+        return String::new();
+    };
+    match (kind.as_str(), value) {
+        ("arrayValue", value) => {
+            let values = array(value, "values").map(any_value).collect::<Vec<_>>();
+            format!("[{}]", values.join(", "))
+        }
+        ("kvlistValue", value) => {
+            let values = array(value, "values")
+                .map(|pair| {
+                    format!(
+                        "{}={}",
+                        pair["key"].as_str().unwrap_or_default(),
+                        any_value(&pair["value"])
+                    )
+                })
+                .collect::<Vec<_>>();
+            format!("{{{}}}", values.join(", "))
+        }
+        (_, Value::String(value)) => value.clone(),
+        (_, value) => value.to_string(),
+    }
+}
+
+fn unix_nanos(value: &Value) -> Option<DateTime<FixedOffset>> {
+    let nanos = match value {
+        Value::String(value) => value.parse::<i64>().ok()?,
+        value => value.as_i64()?,
+    };
+    (nanos > 0).then(|| Utc.timestamp_nanos(nanos).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BATCH: &str = r#"{"resourceLogs":[{"resource":{"attributes":[{"key":"service.name","value":{"stringValue":"checkout"}}]},"scopeLogs":[{"scope":{"name":"orders"},"logRecords":[{"timeUnixNano":"1680343200000000000","severityNumber":17,"body":{"stringValue":"payment failed"},"attributes":[{"key":"attempt","value":{"intValue":"3"}}],"traceId":"abc"},{"observedTimeUnixNano":"1680343201000000000","severityText":"INFO","body":{"kvlistValue":{"values":[{"key":"id","value":{"intValue":"7"}}]}}}]}]}]}"#;
+
+    #[test]
+    fn parses_every_record_of_a_batch() {
+        let entries = OtlpParser.parse_all(BATCH);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].message, "payment failed");
+        assert_eq!(entries[0].source.name, "checkout");
+        assert_eq!(entries[0].level, Some(Level::Error));
+        assert_eq!(entries[0].field("scope"), Some("orders"));
+        assert_eq!(entries[0].field("attempt"), Some("3"));
+        assert_eq!(entries[0].field("trace_id"), Some("abc"));
+        assert_eq!(entries[1].message, "{id=7}");
+        assert_eq!(entries[1].level, Some(Level::Info));
+        assert_eq!(entries[1].date.unwrap().timestamp(), 1_680_343_201);
+    }
+
+    #[test]
+    fn skips_records_without_time() {
+        let line =
+            r#"{"resourceLogs":[{"scopeLogs":[{"logRecords":[{"body":{"stringValue":"x"}}]}]}]}"#;
+        assert!(OtlpParser.parse_all(line).is_empty());
+        assert!(OtlpParser.parse_all("{}").is_empty());
+    }
+}
@@ -0,0 +1,40 @@
+use super::LogParser;
+use crate::file_reader::log_entry::LogEntry;
+
+pub struct PlainTextParser;
+
+impl LogParser for PlainTextParser {
+    fn name(&self) -> &str {
+        "plain"
+    }
+
+    fn description(&self) -> &str {
+        "any text, every line is an entry numbered instead of dated"
+    }
+
+    fn parse(&mut self, line: &str) -> Option<LogEntry> {
+        Some(LogEntry::plain(line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_reader::log_entry::Level;
+
+    #[test]
+    fn parses_every_line_without_date() {
+        let entry = PlainTextParser.parse("[WARN] disk almost full").unwrap();
+        assert_eq!(entry.message, "[WARN] disk almost full");
+        assert_eq!(entry.date, None);
+        assert_eq!(entry.level, Some(Level::Warn));
+    }
+
+    #[test]
+    fn shows_line_number_instead_of_time() {
+        let mut entry = PlainTextParser.parse("text").unwrap();
+        entry.set_line_number(42);
+        assert_eq!(entry.date_time.trim_start(), "42");
+        assert_eq!(entry.date_full(), "line 42");
+    }
+}
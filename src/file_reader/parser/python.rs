@@ -0,0 +1,75 @@
+use super::LogParser;
+use crate::file_reader::log_entry::{ExternalLogMessage, Level, LogEntry};
+use crate::file_reader::timestamp::TimestampParser;
+
+pub struct PythonParser {
+    timestamps: TimestampParser,
+}
+
+impl PythonParser {
+    pub fn new(timestamps: TimestampParser) -> Self {
+        Self { timestamps }
+    }
+}
+
+impl LogParser for PythonParser {
+    fn name(&self) -> &str {
+        "python"
+    }
+
+    fn description(&self) -> &str {
+        "<date> - <logger> - <LEVEL> - <message> lines of Python logging"
+    }
+
+    fn parse(&mut self, line: &str) -> Option<LogEntry> {
+        let mut parts = line.splitn(4, " - ");
+        let (date, name, level) = (parts.next()?, parts.next()?, parts.next()?);
+        let message = parts.next()?;
+        // Default `%(asctime)s` puts a comma before the milliseconds.
+        let date = self.timestamps.parse(&date.replace(',', "."))?;
+        Some(LogEntry::from(ExternalLogMessage {
+            message: message.to_string(),
+            date,
+            source: name.to_string(),
+            level: Some(Level::parse(level)?),
+            fields: Vec::new(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, NaiveDate, TimeZone};
+
+    fn parse(line: &str) -> Option<LogEntry> {
+        PythonParser::new(TimestampParser::default()).parse(line)
+    }
+
+    #[test]
+    fn parses_asctime_as_local_time() {
+        let entry = parse("2023-04-01 10:00:00,123 - app.db - WARNING - slow query").unwrap();
+        let expected = NaiveDate::from_ymd_opt(2023, 4, 1)
+            .and_then(|date| date.and_hms_milli_opt(10, 0, 0, 123))
+            .and_then(|date| Local.from_local_datetime(&date).earliest())
+            .unwrap();
+        assert_eq!(entry.date, Some(expected.into()));
+        assert_eq!(entry.source.name, "app.db");
+        assert_eq!(entry.level, Some(Level::Warn));
+        assert_eq!(entry.message, "slow query");
+    }
+
+    #[test]
+    fn keeps_explicit_offsets() {
+        let entry = parse("2023-04-01T10:00:00+02:00 - app - INFO - started").unwrap();
+        assert_eq!(
+            entry.date.unwrap().to_rfc3339(),
+            "2023-04-01T10:00:00+02:00"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_levels() {
+        assert!(parse("2023-04-01 10:00:00,123 - app - LOUD - started").is_none());
+    }
+}
@@ -0,0 +1,54 @@
+use super::LogParser;
+use crate::file_reader::log_entry::LogEntry;
+use crate::file_reader::timestamp::TimestampParser;
+
+pub struct RawParser {
+    timestamps: TimestampParser,
+}
+
+impl RawParser {
+    pub fn new(timestamps: TimestampParser) -> Self {
+        Self { timestamps }
+    }
+}
+
+impl LogParser for RawParser {
+    fn name(&self) -> &str {
+        "raw"
+    }
+
+    fn description(&self) -> &str {
+        "<date> [<source>]: <message> lines"
+    }
+
+    fn parse(&mut self, line: &str) -> Option<LogEntry> {
+        LogEntry::from_raw(line, &self.timestamps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_date_source_and_message() {
+        let entry = RawParser::new(TimestampParser::default())
+            .parse("2023-04-01T10:00:00Z [worker]: job [ERROR] failed")
+            .unwrap();
+        assert_eq!(entry.source.name, "worker");
+        assert_eq!(entry.message, "job [ERROR] failed");
+    }
+
+    #[test]
+    fn rejects_lines_without_date() {
+        let mut parser = RawParser::new(TimestampParser::default());
+        assert!(parser.parse("started [worker]: job").is_none());
+    }
+
+    #[test]
+    fn rejects_lines_without_bracketed_source() {
+        let mut parser = RawParser::new(TimestampParser::default());
+        assert!(parser.parse("2023-04-01T10:00:00Z café: hello").is_none());
+        assert!(parser.parse("2023-04-01T10:00:00Z INFO started").is_none());
+    }
+}
@@ -0,0 +1,89 @@
+use super::LogParser;
+use crate::config::RegexFormatConfig;
+use crate::file_reader::log_entry::LogEntry;
+use crate::file_reader::timestamp::TimestampParser;
+use regex::Regex;
+
+pub struct RegexParser {
+    name: String,
+    description: String,
+    regex: Regex,
+    timestamps: TimestampParser,
+}
+
+impl RegexParser {
+    pub fn new(format: &RegexFormatConfig, regex: Regex, timestamps: TimestampParser) -> Self {
+        Self {
+            name: format.name.clone(),
+            description: format
+                .description
+                .clone()
+                .unwrap_or_else(|| format.pattern.clone()),
+            regex,
+            timestamps,
+        }
+    }
+}
+
+impl LogParser for RegexParser {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parse(&mut self, line: &str) -> Option<LogEntry> {
+        LogEntry::from_regex(line, &self.regex, &self.timestamps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_reader::log_entry::Level;
+
+    fn parser(pattern: &str) -> RegexParser {
+        let format = RegexFormatConfig {
+            name: "app".to_string(),
+            pattern: pattern.to_string(),
+            date_format: None,
+            description: None,
+        };
+        RegexParser::new(
+            &format,
+            Regex::new(pattern).unwrap(),
+            TimestampParser::default(),
+        )
+    }
+
+    #[test]
+    fn maps_named_groups() {
+        let mut parser = parser(
+            r"^(?P<date>\S+) (?P<level>\w+) (?P<source>\w+) #(?P<request>\d+) (?P<message>.*)$",
+        );
+        let entry = parser
+            .parse("2023-04-01T10:00:00Z warn api #42 slow query")
+            .unwrap();
+        assert_eq!(entry.message, "slow query");
+        assert_eq!(entry.source.name, "api");
+        assert_eq!(entry.level, Some(Level::Warn));
+        assert_eq!(entry.field("request"), Some("42"));
+    }
+
+    #[test]
+    fn finds_bracketed_level_without_level_group() {
+        let mut parser = parser(r"^(?P<date>\S+) (?P<message>.*)$");
+        let entry = parser.parse("2023-04-01T10:00:00Z [ERROR] failed").unwrap();
+        assert_eq!(entry.source.name, "-");
+        assert_eq!(entry.level, Some(Level::Error));
+    }
+
+    #[test]
+    fn describes_with_pattern_by_default() {
+        let parser = parser(r"^(?P<date>\S+) (?P<message>.*)$");
+        assert_eq!(parser.name(), "app");
+        assert_eq!(parser.description(), r"^(?P<date>\S+) (?P<message>.*)$");
+    }
+}
@@ -0,0 +1,209 @@
+use super::LogParser;
+use crate::file_reader::log_entry::{parse_logfmt, ExternalLogMessage, Level, LogEntry};
+use crate::file_reader::timestamp::today_at;
+use chrono::{DateTime, NaiveTime};
+
+pub struct EnvLoggerParser;
+
+pub struct TracingParser;
+
+pub struct TunaParser;
+
+impl LogParser for EnvLoggerParser {
+    fn name(&self) -> &str {
+        "env_logger"
+    }
+
+    fn description(&self) -> &str {
+        "[<timestamp> <LEVEL> <target>] <message> lines of env_logger"
+    }
+
+    fn parse(&mut self, line: &str) -> Option<LogEntry> {
+        let (header, message) = line.strip_prefix('[')?.split_once(']')?;
+        let mut header = header.split_whitespace();
+        let date = DateTime::parse_from_rfc3339(header.next()?).ok()?;
+        let level = Level::parse(header.next()?)?;
+        let source = header.next().unwrap_or("-");
+        if header.next().is_some() {
+            return None;
+        }
+        Some(LogEntry::from(ExternalLogMessage {
+            message: message.strip_prefix(' ').unwrap_or(message).to_string(),
+            date,
+            source: source.to_string(),
+            level: Some(level),
+            fields: Vec::new(),
+        }))
+    }
+}
+
+impl LogParser for TracingParser {
+    fn name(&self) -> &str {
+        "tracing"
+    }
+
+    fn description(&self) -> &str {
+        "<timestamp> <LEVEL> <spans>: <target>: <message> lines of tracing-subscriber"
+    }
+
+    fn parse(&mut self, line: &str) -> Option<LogEntry> {
+        let (date, rest) = line.split_once(' ')?;
+        let date = DateTime::parse_from_rfc3339(date).ok()?;
+        let (level, rest) = rest.trim_start().split_once(' ')?;
+        let level = Level::parse(level)?;
+        let (spans, rest) = split_spans(rest);
+        let (source, message) = match rest.split_once(": ") {
+            Some((target, message)) if is_target(target) => (target, message),
+            _ => ("-", rest),
+        };
+        let mut fields = Vec::new();
+        if !spans.is_empty() {
+            let names = spans.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+            fields.push(("span".to_string(), names.join(":")));
+        }
+        for (name, span_fields) in spans {
+            match parse_logfmt(span_fields) {
+                Some(pairs) => fields.extend(
+                    pairs
+                        .into_iter()
+                        .map(|(key, value)| (format!("{name}.{key}"), value)),
+                ),
+                None if !span_fields.is_empty() => {
+                    fields.push((name.to_string(), span_fields.to_string()))
+                }
+                None => {}
+            }
+        }
+        Some(LogEntry::from(ExternalLogMessage {
+            message: message.to_string(),
+            date,
+            source: source.to_string(),
+            level: Some(level),
+            fields,
+        }))
+    }
+}
+
+impl LogParser for TunaParser {
+    fn name(&self) -> &str {
+        "tuna"
+    }
+
+    fn description(&self) -> &str {
+        "<HH:MM:SS.mmm> [<LEVEL>] - <message> lines of tuna's own log"
+    }
+
+    fn parse(&mut self, line: &str) -> Option<LogEntry> {
+        let (time, rest) = line.split_once(' ')?;
+        let time = NaiveTime::parse_from_str(time, "%H:%M:%S%.3f").ok()?;
+        let (level, message) = rest.strip_prefix('[')?.split_once("] - ")?;
+        let date = today_at(time)?;
+        Some(LogEntry::from(ExternalLogMessage {
+            message: message.to_string(),
+            date,
+            source: "tuna".to_string(),
+            level: Some(Level::parse(level)?),
+            fields: Vec::new(),
+        }))
+    }
+}
+
+/// Splits leading `name{fields}:` spans, which tracing prints before the target.
+fn split_spans(line: &str) -> (Vec<(&str, &str)>, &str) {
+    let mut spans = Vec::new();
+    let mut rest = line;
+    while let Some(open) = rest.find('{') {
+        let name = &rest[..open];
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            break;
+        }
+        let Some(close) = find_closing_brace(&rest[open..]) else {
+            break;
+        };
+        spans.push((name, &rest[open + 1..open + close]));
+        match rest[open + close + 1..].strip_prefix(':') {
+            Some(remaining) => rest = remaining,
+            None => return (Vec::new(), line),
+        }
+    }
+    if spans.is_empty() {
+        return (spans, line);
+    }
+    match rest.strip_prefix(' ') {
+        Some(rest) => (spans, rest),
+        None => (Vec::new(), line),
+    }
+}
+
+fn find_closing_brace(value: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut is_quoted = false;
+    let mut is_escaped = false;
+    for (index, c) in value.char_indices() {
+        match c {
+            _ if is_escaped => is_escaped = false,
+            '\\' if is_quoted => is_escaped = true,
+            '"' => is_quoted = !is_quoted,
+            '{' if !is_quoted => depth += 1,
+            '}' if !is_quoted => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_target(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | ':' | '-'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_env_logger_line() {
+        let entry = EnvLoggerParser
+            .parse("[2023-04-01T10:00:00Z WARN  hyper::proto] connection reset")
+            .unwrap();
+        assert_eq!(entry.message, "connection reset");
+        assert_eq!(entry.source.name, "hyper::proto");
+        assert_eq!(entry.level, Some(Level::Warn));
+    }
+
+    #[test]
+    fn parses_tracing_spans() {
+        let line = r#"2023-04-01T10:00:00.123456Z  INFO request{method=GET path="/a b"}: api::handler: handled"#;
+        let entry = TracingParser.parse(line).unwrap();
+        assert_eq!(entry.message, "handled");
+        assert_eq!(entry.source.name, "api::handler");
+        assert_eq!(entry.level, Some(Level::Info));
+        assert_eq!(entry.field("span"), Some("request"));
+        assert_eq!(entry.field("request.method"), Some("GET"));
+        assert_eq!(entry.field("request.path"), Some("/a b"));
+    }
+
+    #[test]
+    fn parses_tracing_line_without_target() {
+        let entry = TracingParser
+            .parse("2023-04-01T10:00:00Z ERROR request failed: timeout")
+            .unwrap();
+        assert_eq!(entry.source.name, "-");
+        assert_eq!(entry.message, "request failed: timeout");
+    }
+
+    #[test]
+    fn parses_tuna_line() {
+        let entry = TunaParser.parse("10:00:00.123 [INFO] - started").unwrap();
+        assert_eq!(entry.message, "started");
+        assert_eq!(entry.source.name, "tuna");
+        assert_eq!(entry.date_time, "10:00:00.123");
+    }
+}
@@ -0,0 +1,263 @@
+use super::LogParser;
+use crate::file_reader::log_entry::{ExternalLogMessage, Level, LogEntry};
+use crate::file_reader::timestamp::with_inferred_year;
+use chrono::{DateTime, FixedOffset};
+
+const FACILITIES: [&str; 24] = [
+    "kern",
+    "user",
+    "mail",
+    "daemon",
+    "auth",
+    "syslog",
+    "lpr",
+    "news",
+    "uucp",
+    "cron",
+    "authpriv",
+    "ftp",
+    "ntp",
+    "security",
+    "console",
+    "solaris-cron",
+    "local0",
+    "local1",
+    "local2",
+    "local3",
+    "local4",
+    "local5",
+    "local6",
+    "local7",
+];
+const BSD_TIMESTAMP_LEN: usize = 15;
+const NIL_VALUE: &str = "-";
+
+pub struct Rfc3164Parser;
+
+pub struct Rfc5424Parser;
+
+impl LogParser for Rfc3164Parser {
+    fn name(&self) -> &str {
+        "syslog"
+    }
+
+    fn description(&self) -> &str {
+        "BSD syslog (RFC 3164) lines as found in /var/log/syslog"
+    }
+
+    fn parse(&mut self, line: &str) -> Option<LogEntry> {
+        let (priority, rest) = parse_priority(line);
+        let (date, rest) = parse_bsd_timestamp(rest).or_else(|| {
+            next_token(rest).and_then(|(date, rest)| {
+                DateTime::parse_from_rfc3339(date)
+                    .ok()
+                    .map(|date| (date, rest))
+            })
+        })?;
+        let (host, rest) = next_token(rest)?;
+        let mut fields = vec![("host".to_string(), host.to_string())];
+        let (source, message) = match rest.split_once(':') {
+            Some((tag, message)) if !tag.is_empty() && !tag.contains(char::is_whitespace) => {
+                let source = match tag.strip_suffix(']').and_then(|tag| tag.split_once('[')) {
+                    Some((name, pid)) => {
+                        fields.push(("pid".to_string(), pid.to_string()));
+                        name
+                    }
+                    None => tag,
+                };
+                (source, message.strip_prefix(' ').unwrap_or(message))
+            }
+            _ => (host, rest),
+        };
+        let level = match priority {
+            Some(priority) => {
+                push_facility(priority, &mut fields);
+                Level::from_syslog_severity(priority % 8)
+            }
+            None => Level::find_bracketed(message),
+        };
+        Some(LogEntry::from(ExternalLogMessage {
+            message: message.to_string(),
+            date,
+            source: source.to_string(),
+            level,
+            fields,
+        }))
+    }
+}
+
+impl LogParser for Rfc5424Parser {
+    fn name(&self) -> &str {
+        "rfc5424"
+    }
+
+    fn description(&self) -> &str {
+        "<pri>1 <timestamp> <host> <app> <procid> <msgid> [structured data] <message>"
+    }
+
+    fn parse(&mut self, line: &str) -> Option<LogEntry> {
+        let (priority, rest) = parse_priority(line);
+        let priority = priority?;
+        let rest = rest.strip_prefix("1 ")?;
+        let (date, rest) = next_token(rest)?;
+        let date = DateTime::parse_from_rfc3339(date).ok()?;
+        let (host, rest) = next_token(rest)?;
+        let (app_name, rest) = next_token(rest)?;
+        let (proc_id, rest) = next_token(rest)?;
+        let (message_id, rest) = next_token(rest)?;
+
+        let mut fields = Vec::new();
+        let header = [("host", host), ("pid", proc_id), ("msgid", message_id)];
+        for (key, value) in header {
+            if value != NIL_VALUE {
+                fields.push((key.to_string(), value.to_string()));
+            }
+        }
+        push_facility(priority, &mut fields);
+        let rest = parse_structured_data(rest, &mut fields)?;
+        let message = rest.strip_prefix(' ').unwrap_or(rest);
+        let message = message.strip_prefix('\u{feff}').unwrap_or(message);
+        Some(LogEntry::from(ExternalLogMessage {
+            message: message.to_string(),
+            date,
+            source: app_name.to_string(),
+            level: Level::from_syslog_severity(priority % 8),
+            fields,
+        }))
+    }
+}
+
+fn parse_priority(line: &str) -> (Option<u8>, &str) {
+    let priority = line
+        .strip_prefix('<')
+        .and_then(|rest| rest.split_once('>'))
+        .filter(|(priority, _)| {
+            (1..=3).contains(&priority.len()) && priority.chars().all(|c| c.is_ascii_digit())
+        })
+        .and_then(|(priority, rest)| Some((priority.parse::<u8>().ok()?, rest)));
+    match priority {
+        Some((priority, rest)) => (Some(priority), rest),
+        None => (None, line),
+    }
+}
+
+fn parse_bsd_timestamp(line: &str) -> Option<(DateTime<FixedOffset>, &str)> {
+    if !line.is_char_boundary(BSD_TIMESTAMP_LEN) {
+        return None;
+    }
+    let (timestamp, rest) = line.split_at(BSD_TIMESTAMP_LEN);
+    let date = with_inferred_year(timestamp, "%b %e %H:%M:%S")?;
+    Some((date, rest.strip_prefix(' ')?))
+}
+
+fn parse_structured_data<'a>(
+    mut rest: &'a str,
+    fields: &mut Vec<(String, String)>,
+) -> Option<&'a str> {
+    if let Some(rest) = rest.strip_prefix(NIL_VALUE) {
+        return Some(rest);
+    }
+    if !rest.starts_with('[') {
+        return None;
+    }
+    while let Some(element) = rest.strip_prefix('[') {
+        let id_end = element.find([' ', ']'])?;
+        let id = &element[..id_end];
+        rest = &element[id_end..];
+        loop {
+            rest = rest.trim_start_matches(' ');
+            if let Some(remaining) = rest.strip_prefix(']') {
+                rest = remaining;
+                break;
+            }
+            let (name, remaining) = rest.split_once("=\"")?;
+            let (value, remaining) = parse_param_value(remaining)?;
+            fields.push((format!("{id}.{name}"), value));
+            rest = remaining;
+        }
+    }
+    Some(rest)
+}
+
+fn parse_param_value(value: &str) -> Option<(String, &str)> {
+    let mut result = String::new();
+    let mut chars = value.char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => return Some((result, &value[index + 1..])),
+            '\\' => match chars.next()?.1 {
+                c @ ('"' | '\\' | ']') => result.push(c),
+                c => {
+                    result.push('\\');
+                    result.push(c);
+                }
+            },
+            c => result.push(c),
+        }
+    }
+    None
+}
+
+fn push_facility(priority: u8, fields: &mut Vec<(String, String)>) {
+    if let Some(facility) = FACILITIES.get(usize::from(priority / 8)) {
+        fields.push(("facility".to_string(), facility.to_string()));
+    }
+}
+
+fn next_token(line: &str) -> Option<(&str, &str)> {
+    let (token, rest) = line.split_once(' ')?;
+    (!token.is_empty()).then_some((token, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bsd_line_with_pid() {
+        let line = "Apr  1 10:00:00 web1 sshd[1234]: Accepted publickey for deploy";
+        let entry = Rfc3164Parser.parse(line).unwrap();
+        assert_eq!(entry.message, "Accepted publickey for deploy");
+        assert_eq!(entry.source.name, "sshd");
+        assert_eq!(entry.field("host"), Some("web1"));
+        assert_eq!(entry.field("pid"), Some("1234"));
+        assert_eq!(entry.date_time, "10:00:00.000");
+    }
+
+    #[test]
+    fn parses_bsd_priority() {
+        let line = "<34>Apr  1 10:00:00 web1 su: 'su root' failed";
+        let entry = Rfc3164Parser.parse(line).unwrap();
+        assert_eq!(entry.level, Some(Level::Fatal));
+        assert_eq!(entry.field("facility"), Some("auth"));
+    }
+
+    #[test]
+    fn parses_rfc5424_structured_data() {
+        let line = r#"<165>1 2023-04-01T10:00:00.003Z web1 app 42 ID47 [origin ip="10.0.0.1" note="a \"b\""] started"#;
+        let entry = Rfc5424Parser.parse(line).unwrap();
+        assert_eq!(entry.message, "started");
+        assert_eq!(entry.source.name, "app");
+        assert_eq!(entry.level, Some(Level::Info));
+        assert_eq!(entry.field("facility"), Some("local4"));
+        assert_eq!(entry.field("msgid"), Some("ID47"));
+        assert_eq!(entry.field("origin.ip"), Some("10.0.0.1"));
+        assert_eq!(entry.field("origin.note"), Some("a \"b\""));
+    }
+
+    #[test]
+    fn skips_nil_values() {
+        let line = "<14>1 2023-04-01T10:00:00Z - app - - - message";
+        let entry = Rfc5424Parser.parse(line).unwrap();
+        assert_eq!(entry.message, "message");
+        assert_eq!(entry.field("host"), None);
+        assert_eq!(entry.field("pid"), None);
+    }
+
+    #[test]
+    fn rejects_rfc5424_without_version() {
+        assert!(Rfc5424Parser
+            .parse("<14>2023-04-01T10:00:00Z host app - - - message")
+            .is_none());
+    }
+}
@@ -0,0 +1,156 @@
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, NaiveDateTime, NaiveTime, TimeZone, Utc,
+};
+use serde_json::Value;
+
+const DEFAULT_PATTERNS: [&str; 3] = [
+    "%e %b %Y %H:%M:%S%.3f %z",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+];
+const MIN_EPOCH_DIGITS: usize = 9;
+
+#[derive(Clone)]
+enum TimestampFormat {
+    Pattern(String),
+    Rfc3339,
+    Rfc2822,
+    Epoch,
+}
+
+/// Tries its formats in order, the first one parsing a value wins.
+#[derive(Clone)]
+pub struct TimestampParser {
+    formats: Vec<TimestampFormat>,
+}
+
+impl TimestampParser {
+    pub fn new(patterns: &[String]) -> Self {
+        let formats = patterns
+            .iter()
+            .map(String::as_str)
+            .chain(DEFAULT_PATTERNS)
+            .map(|pattern| TimestampFormat::Pattern(pattern.to_string()))
+            .chain([
+                TimestampFormat::Rfc3339,
+                TimestampFormat::Rfc2822,
+                TimestampFormat::Epoch,
+            ])
+            .collect();
+        Self { formats }
+    }
+
+    pub fn parse(&self, value: &str) -> Option<DateTime<FixedOffset>> {
+        let value = value.trim();
+        if value.is_empty() {
+            return None;
+        }
+        self.formats.iter().find_map(|format| format.parse(value))
+    }
+
+    pub fn parse_json(&self, value: &Value) -> Option<DateTime<FixedOffset>> {
+        match value {
+            Value::String(value) => self.parse(value),
+            Value::Number(number) => match number.as_i64() {
+                Some(value) => from_epoch(value, 0),
+                None => number.as_f64().and_then(|value| {
+                    from_epoch(value.trunc() as i64, (value.fract() * 1e9) as u32)
+                }),
+            },
+            _ => None,
+        }
+    }
+}
+
+impl Default for TimestampParser {
+    /// Parser of the built-in formats only.
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+
+impl TimestampFormat {
+    fn parse(&self, value: &str) -> Option<DateTime<FixedOffset>> {
+        match self {
+            TimestampFormat::Pattern(pattern) => {
+                DateTime::parse_from_str(value, pattern).ok().or_else(|| {
+                    NaiveDateTime::parse_from_str(value, pattern)
+                        .ok()
+                        .and_then(local_date)
+                })
+            }
+            TimestampFormat::Rfc3339 => DateTime::parse_from_rfc3339(value).ok(),
+            TimestampFormat::Rfc2822 => DateTime::parse_from_rfc2822(value).ok(),
+            TimestampFormat::Epoch => {
+                let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+                let is_number = integer.len() >= MIN_EPOCH_DIGITS
+                    && integer.chars().all(|c| c.is_ascii_digit())
+                    && fraction.chars().all(|c| c.is_ascii_digit());
+                if !is_number {
+                    return None;
+                }
+                let nanos = format!("{fraction:0<9}");
+                from_epoch(integer.parse().ok()?, nanos[..9].parse().ok()?)
+            }
+        }
+    }
+}
+
+fn from_epoch(value: i64, fraction_nanos: u32) -> Option<DateTime<FixedOffset>> {
+    if value < 0 {
+        return None;
+    }
+    let (seconds, nanos) = match value {
+        v if v < 100_000_000_000 => (value, fraction_nanos),
+        v if v < 100_000_000_000_000 => (value / 1_000, (value % 1_000) as u32 * 1_000_000),
+        v if v < 100_000_000_000_000_000 => (value / 1_000_000, (value % 1_000_000) as u32 * 1_000),
+        _ => (value / 1_000_000_000, (value % 1_000_000_000) as u32),
+    };
+    Utc.timestamp_opt(seconds, nanos)
+        .single()
+        .map(|date| date.into())
+}
+
+/// Dates time-only timestamps with the current local day.
+pub fn today_at(time: NaiveTime) -> Option<DateTime<FixedOffset>> {
+    local_date(Local::now().date_naive().and_time(time))
+}
+
+/// Local timestamps without a year get the current one, unless that would
+/// put them in the future, e.g. December logs read in January.
+pub fn with_inferred_year(timestamp: &str, pattern: &str) -> Option<DateTime<FixedOffset>> {
+    let now: DateTime<FixedOffset> = Local::now().into();
+    let pattern = format!("%Y {pattern}");
+    let parse = |year: i32| {
+        NaiveDateTime::parse_from_str(&format!("{year} {timestamp}"), &pattern)
+            .ok()
+            .and_then(local_date)
+    };
+    let date = parse(now.year())?;
+    if date > now + Duration::days(1) {
+        parse(now.year() - 1)
+    } else {
+        Some(date)
+    }
+}
+
+/// Keeps the local offset, converting `DateTime<Local>` with `into` sets it to zero.
+fn local_date(date: NaiveDateTime) -> Option<DateTime<FixedOffset>> {
+    let date = Local.from_local_datetime(&date).earliest()?;
+    Some(date.with_timezone(date.offset()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefers_earlier_patterns_after_a_later_one_matched() {
+        let patterns = ["%d.%m.%Y %H:%M:%S", "%m.%d.%Y %H:%M:%S"].map(String::from);
+        let parser = TimestampParser::new(&patterns);
+        let date = parser.parse("04.13.2023 10:00:00").unwrap();
+        assert_eq!((date.month(), date.day()), (4, 13));
+        let date = parser.parse("01.02.2023 10:00:00").unwrap();
+        assert_eq!((date.month(), date.day()), (2, 1));
+    }
+}
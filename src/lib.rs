@@ -1,25 +1,181 @@
+use config::Config;
+use file_reader::continuation::ContinuationPolicy;
+use file_reader::detect::{self, Sample};
+use file_reader::diagnostics::Diagnostics;
+use file_reader::follow::FollowState;
+use file_reader::parser::ParserRegistry;
+use file_reader::LogInput;
+use itertools::Itertools;
+use options::{Input, Options};
 use std::fs::File;
+use std::io::{BufRead, Cursor, Read};
+pub mod config;
 pub mod file_reader;
+pub mod options;
 pub mod ui;
 
-pub fn handle_file(path: String) {
+pub fn handle_input(options: Options) {
+    let config = match load_config(&options) {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("{error}");
+            return;
+        }
+    };
+    let continuation = match ContinuationPolicy::new(&config.continuation) {
+        Ok(continuation) => continuation,
+        Err(error) => {
+            eprintln!("{error}");
+            return;
+        }
+    };
+    let registry = ParserRegistry::new(&config);
+    let diagnostics = Diagnostics::default();
+    let mut inputs = Vec::with_capacity(options.inputs.len());
+    for input in &options.inputs {
+        match open_input(input, &options, &registry, &continuation, &diagnostics) {
+            Ok(input) => inputs.push(input),
+            Err(error) => {
+                eprintln!("{error}");
+                return;
+            }
+        }
+    }
+    let format = inputs
+        .iter()
+        .map(|input| input.parser.name())
+        .unique()
+        .join(", ");
+    let is_streaming = inputs.iter().any(|input| input.follow.is_some())
+        || options
+            .inputs
+            .iter()
+            .any(|input| matches!(input, Input::Stdin));
+
     let (sender, receiver) = crossbeam_channel::bounded(100);
     let mut term = ui::TermUI::new();
     let callback = term.callback().clone();
-    let path = std::path::Path::new(&path);
-    let is_raw_file = path.extension().map(|v| v == "log").unwrap_or(false);
-    match File::open(path) {
-        Ok(file) => {
-            std::thread::Builder::new()
-                .name("file_processing".into())
-                .spawn(move || {
-                    file_reader::read_file(file, is_raw_file, sender, callback);
-                })
-                .unwrap();
-            term.run(receiver);
+    if inputs.len() == 1 {
+        let input = inputs.pop().unwrap();
+        spawn_reader(input, sender, callback);
+    } else {
+        let receivers = inputs
+            .into_iter()
+            .map(|input| {
+                let (sender, receiver) = crossbeam_channel::bounded(100);
+                spawn_reader(input, sender, callback.clone());
+                receiver
+            })
+            .collect();
+        std::thread::Builder::new()
+            .name("inputs_merge".into())
+            .spawn(move || {
+                file_reader::merge::merge(receivers, is_streaming, sender, callback);
+            })
+            .unwrap();
+    }
+    term.run(receiver, format, is_streaming, diagnostics);
+}
+
+fn load_config(options: &Options) -> Result<Config, String> {
+    let mut config = Config::load(options.config.as_deref())?;
+    config.json.override_with(&options.json);
+    config.json.validate()?;
+    config.logfmt.override_with(&options.logfmt);
+    config.container.override_with(&options.container);
+    config.csv.override_with(&options.csv);
+    config.continuation.override_with(&options.continuation);
+    let mut timestamp_formats = options.timestamp_formats.clone();
+    timestamp_formats.append(&mut config.timestamp_formats);
+    config.timestamp_formats = timestamp_formats;
+    Ok(config)
+}
+
+fn open_input(
+    input: &Input,
+    options: &Options,
+    registry: &ParserRegistry,
+    continuation: &ContinuationPolicy,
+    diagnostics: &Diagnostics,
+) -> Result<LogInput, String> {
+    let mut follow = None;
+    let mut sample = None;
+    let (name, mut reader): (String, Box<dyn BufRead + Send>) = match input {
+        Input::File(path) => {
+            let file =
+                File::open(path).map_err(|error| format!("Failed to open {path}: {error}"))?;
+            if options.follow {
+                follow = FollowState::new(path.into(), &file, options.encoding).ok();
+            }
+            let reader = match &follow {
+                Some(follow) => follow.reader(file),
+                None => Box::new(file_reader::decode(file, options.encoding)),
+            };
+            (path.clone(), reader)
+        }
+        Input::Stdin => {
+            let mut reader = file_reader::decode(std::io::stdin(), options.encoding);
+            if options.format.is_none() {
+                let read = Sample::read_buffered(&mut reader);
+                sample = Some(read.map_err(|error| format!("Failed to read stdin: {error}"))?);
+            }
+            ("stdin".into(), Box::new(reader))
+        }
+    };
+    let parser = match options.format.as_deref() {
+        Some(format) => registry.create(format).ok_or_else(|| {
+            let formats = registry
+                .formats()
+                .map(|parser| parser.name().to_string())
+                .join(", ");
+            format!("{name}: Unknown log format: {format}. Use --format with one of: {formats}")
+        })?,
+        None => {
+            let sample = match sample {
+                Some(sample) => sample,
+                None => Sample::read(&mut reader)
+                    .map_err(|error| format!("Failed to read {name}: {error}"))?,
+            };
+            let parser = detect::detect_parser(&sample, registry)
+                .map(|detection| detection.parser)
+                .unwrap_or_else(|| registry.fallback());
+            reader = Box::new(Cursor::new(sample.bytes).chain(reader));
+            parser
         }
+    };
+    log::info!("Reading {name} with {} format", parser.name());
+    Ok(LogInput {
+        name,
+        reader,
+        follow,
+        parser,
+        continuation: continuation.clone(),
+        diagnostics: diagnostics.clone(),
+    })
+}
+
+fn spawn_reader(
+    input: LogInput,
+    sender: crossbeam_channel::Sender<file_reader::log_entry::LogEntry>,
+    callback: cursive::CbSink,
+) {
+    std::thread::Builder::new()
+        .name(format!("input_processing:{}", input.name))
+        .spawn(move || {
+            file_reader::read_file(input, sender, callback);
+        })
+        .unwrap();
+}
+
+pub fn list_formats(options: &Options) {
+    let config = match load_config(options) {
+        Ok(config) => config,
         Err(error) => {
-            eprintln!("Failed to open file: {error}");
+            eprintln!("{error}");
+            return;
         }
+    };
+    for parser in ParserRegistry::new(&config).formats() {
+        println!("{:<12} {}", parser.name(), parser.description());
     }
 }
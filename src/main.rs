@@ -3,16 +3,53 @@ use env_logger::{Builder, Target};
 use log::LevelFilter;
 use std::io::Write;
 use std::{path::Path, process::exit};
+use tuna::config::{ContinuationMode, JsonMapping};
+use tuna::options::Command;
 
 fn main() {
     configure_logging();
-    let file_path = std::env::args().nth(1);
-    match file_path {
-        Some(path) => {
-            tuna::handle_file(path);
+    match Command::parse(std::env::args().skip(1)) {
+        Ok(Command::Open(options)) => {
+            tuna::handle_input(*options);
         }
-        None => {
-            println!("Usage: tuna <path to log file>");
+        Ok(Command::ListFormats(options)) => {
+            tuna::list_formats(&options);
+        }
+        Err(error) => {
+            println!("{error}");
+            println!("Usage: tuna [options] <path to log file | ->...");
+            println!("       tuna --list-formats [options]");
+            println!();
+            println!("Options:");
+            println!("  -f, --follow              keep reading appended lines");
+            println!("  --format <name>           skip format detection");
+            println!(
+                "  --config <path>           config file, ~/.config/tuna/config.json by default"
+            );
+            println!(
+                "  --json-preset <name>      JSON keys preset: {}",
+                JsonMapping::PRESETS.join(", ")
+            );
+            println!("  --json-message <path>     JSON key of the message, e.g. log.message");
+            println!("  --json-timestamp <path>   JSON key of the timestamp");
+            println!("  --json-source <path>      JSON key of the source");
+            println!("  --json-level <path>       JSON key of the level");
+            println!("  --logfmt-source <key>     logfmt key of the source, service by default");
+            println!("  --container-json          decode JSON payloads of docker and cri logs");
+            println!("  --csv-message <column>    CSV/TSV column of the message, guessed by name");
+            println!("  --csv-timestamp <column>  CSV/TSV column of the timestamp");
+            println!("  --csv-source <column>     CSV/TSV column of the source");
+            println!("  --csv-level <column>      CSV/TSV column of the level");
+            println!(
+                "  --continuation <mode>     unparsed lines handling: {}",
+                ContinuationMode::NAMES.join(", ")
+            );
+            println!("  --new-entry-pattern <re>  unparsed lines matching it start a new entry");
+            println!("  --max-entry-lines <n>     lines appended to one entry at most");
+            println!(
+                "  --encoding <label>        input encoding, e.g. utf-16le, latin1, windows-1252"
+            );
+            println!("  --timestamp-format <fmt>  strftime pattern tried before the built-in ones");
             exit(1);
         }
     }
@@ -0,0 +1,136 @@
+use crate::config::{
+    ContainerConfig, ContinuationConfig, ContinuationMode, CsvMappingConfig, JsonMappingConfig,
+    LogfmtConfig,
+};
+use encoding_rs::Encoding;
+use std::io::IsTerminal;
+
+pub enum Command {
+    Open(Box<Options>),
+    /// Lists the built-in formats and the regex formats of the config.
+    ListFormats(Box<Options>),
+}
+
+pub enum Input {
+    File(String),
+    Stdin,
+}
+
+pub struct Options {
+    pub inputs: Vec<Input>,
+    pub format: Option<String>,
+    pub follow: bool,
+    pub config: Option<String>,
+    pub json: JsonMappingConfig,
+    pub logfmt: LogfmtConfig,
+    pub container: ContainerConfig,
+    pub csv: CsvMappingConfig,
+    pub continuation: ContinuationConfig,
+    pub encoding: Option<&'static Encoding>,
+    pub timestamp_formats: Vec<String>,
+}
+
+impl Command {
+    pub fn parse<I>(mut args: I) -> Result<Self, String>
+    where
+        I: Iterator<Item = String>,
+    {
+        let mut inputs = Vec::new();
+        let mut format = None;
+        let mut follow = false;
+        let mut list_formats = false;
+        let mut config = None;
+        let mut json = JsonMappingConfig::default();
+        let mut logfmt = LogfmtConfig::default();
+        let mut container = ContainerConfig::default();
+        let mut csv = CsvMappingConfig::default();
+        let mut continuation = ContinuationConfig::default();
+        let mut encoding = None;
+        let mut timestamp_formats = Vec::new();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--list-formats" => list_formats = true,
+                "--format" => format = Some(value(&mut args, &arg)?),
+                "--config" => config = Some(value(&mut args, &arg)?),
+                "--json-preset" => json.preset = Some(value(&mut args, &arg)?),
+                "--json-message" => json.message = Some(value(&mut args, &arg)?),
+                "--json-timestamp" => json.timestamp = Some(value(&mut args, &arg)?),
+                "--json-source" => json.source = Some(value(&mut args, &arg)?),
+                "--json-level" => json.level = Some(value(&mut args, &arg)?),
+                "--logfmt-source" => logfmt.source = Some(value(&mut args, &arg)?),
+                "--container-json" => container.decode_json = true,
+                "--csv-message" => csv.message = Some(value(&mut args, &arg)?),
+                "--csv-timestamp" => csv.timestamp = Some(value(&mut args, &arg)?),
+                "--csv-source" => csv.source = Some(value(&mut args, &arg)?),
+                "--csv-level" => csv.level = Some(value(&mut args, &arg)?),
+                "--continuation" => {
+                    let mode = value(&mut args, &arg)?;
+                    continuation.mode = Some(ContinuationMode::parse(&mode).ok_or_else(|| {
+                        format!(
+                            "Unknown continuation mode: {mode}. Available modes: {}",
+                            ContinuationMode::NAMES.join(", ")
+                        )
+                    })?);
+                }
+                "--new-entry-pattern" => {
+                    continuation.new_entry_pattern = Some(value(&mut args, &arg)?)
+                }
+                "--max-entry-lines" => {
+                    let max_lines = value(&mut args, &arg)?;
+                    continuation.max_lines = Some(
+                        max_lines
+                            .parse()
+                            .map_err(|_| format!("{arg} requires a number, got {max_lines}"))?,
+                    );
+                }
+                "--encoding" => {
+                    let label = value(&mut args, &arg)?;
+                    encoding = Some(
+                        Encoding::for_label(label.as_bytes())
+                            .ok_or_else(|| format!("Unknown encoding: {label}"))?,
+                    );
+                }
+                "--timestamp-format" => timestamp_formats.push(value(&mut args, &arg)?),
+                "-f" | "--follow" => follow = true,
+                "-" => inputs.push(Input::Stdin),
+                _ if arg.starts_with("--") => return Err(format!("Unknown option: {arg}")),
+                _ => inputs.push(Input::File(arg)),
+            }
+        }
+        if inputs.is_empty() && !list_formats {
+            if std::io::stdin().is_terminal() {
+                return Err("Missing path to log file".into());
+            }
+            inputs.push(Input::Stdin);
+        }
+        if inputs.iter().filter(|i| matches!(i, Input::Stdin)).count() > 1 {
+            return Err("Standard input can only be read once".into());
+        }
+        let options = Box::new(Options {
+            inputs,
+            format,
+            follow,
+            config,
+            json,
+            logfmt,
+            container,
+            csv,
+            continuation,
+            encoding,
+            timestamp_formats,
+        });
+        if list_formats {
+            Ok(Command::ListFormats(options))
+        } else {
+            Ok(Command::Open(options))
+        }
+    }
+}
+
+fn value<I>(args: &mut I, option: &str) -> Result<String, String>
+where
+    I: Iterator<Item = String>,
+{
+    args.next()
+        .ok_or_else(|| format!("{option} requires a value"))
+}
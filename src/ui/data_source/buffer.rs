@@ -6,19 +6,28 @@ use crossbeam_channel::Receiver;
 pub struct Buffer {
     buffer: Vec<LogEntry>,
     receiver: Option<Receiver<LogEntry>>,
+    is_streaming: bool,
 }
 
 impl Buffer {
-    pub fn new(receiver: Receiver<LogEntry>) -> Self {
+    pub fn new(receiver: Receiver<LogEntry>, is_streaming: bool) -> Self {
         Self {
             buffer: Vec::new(),
             receiver: Some(receiver),
+            is_streaming,
         }
     }
 
     pub fn inner(&self) -> &[LogEntry] {
         &self.buffer
     }
+
+    /// Takes an entry only if one was already read, even when not streaming.
+    pub fn try_take_next(&mut self) -> Option<&LogEntry> {
+        let entry = self.receiver.as_ref()?.try_recv().ok()?;
+        self.buffer.push(entry);
+        self.buffer.last()
+    }
 }
 
 impl SearchSourceBuffer for Buffer {
@@ -32,7 +41,12 @@ impl SearchSourceBuffer for Buffer {
 
     fn take_next(&mut self) -> Option<&LogEntry> {
         let receiver = self.receiver.as_mut().unwrap();
-        if let Ok(entry) = receiver.recv() {
+        let result = if self.is_streaming {
+            receiver.try_recv().ok()
+        } else {
+            receiver.recv().ok()
+        };
+        if let Some(entry) = result {
             self.buffer.push(entry);
             self.buffer.last()
         } else {
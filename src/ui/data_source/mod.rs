@@ -1,13 +1,14 @@
 use self::buffer::Buffer;
 use self::search_state::{SearchSlice, SearchSourceBuffer, SearchState};
-use crate::file_reader::log_entry::{LogEntry, Source};
+use crate::file_reader::log_entry::{Level, LogEntry, Source};
 use crossbeam_channel::Receiver;
+use itertools::Itertools;
 use std::{collections::HashSet, ops::Range};
 
 mod buffer;
 mod search_state;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct PaginationState {
     pub current: usize,
     pub total: Option<usize>,
@@ -19,24 +20,109 @@ pub enum SearchPaginationState {
     MatchesIteration(PaginationState),
 }
 
+#[derive(Clone, Default, PartialEq)]
+struct Filter {
+    sources: Option<HashSet<u64>>,
+    files: Option<HashSet<u64>>,
+    min_level: Option<Level>,
+    fields: Vec<FieldFilter>,
+}
+
+#[derive(Clone, PartialEq)]
+struct FieldFilter {
+    key: String,
+    value: Option<String>,
+}
+
+impl Filter {
+    fn is_empty(&self) -> bool {
+        self.sources.is_none()
+            && self.files.is_none()
+            && self.min_level.is_none()
+            && self.fields.is_empty()
+    }
+
+    fn matches(&self, entry: &LogEntry) -> bool {
+        if entry.is_separator {
+            return true;
+        }
+        let is_file_selected = match &entry.file {
+            Some(file) => Filter::contains(&self.files, file),
+            None => true,
+        };
+        let is_level_selected = match (self.min_level, entry.level) {
+            (Some(min_level), Some(level)) => level >= min_level,
+            _ => true,
+        };
+        is_file_selected
+            && is_level_selected
+            && Filter::contains(&self.sources, &entry.source)
+            && self.fields.iter().all(|field| field.matches(entry))
+    }
+
+    fn contains(selected: &Option<HashSet<u64>>, source: &Source) -> bool {
+        match selected {
+            Some(selected) => selected.contains(&source.hash),
+            None => true,
+        }
+    }
+}
+
+impl FieldFilter {
+    fn parse(query: &str) -> Vec<Self> {
+        query
+            .split_whitespace()
+            .map(|term| match term.split_once('=') {
+                Some((key, value)) => FieldFilter {
+                    key: key.to_string(),
+                    value: Some(value.to_string()),
+                },
+                None => FieldFilter {
+                    key: term.to_string(),
+                    value: None,
+                },
+            })
+            .collect()
+    }
+
+    fn matches(&self, entry: &LogEntry) -> bool {
+        match (entry.field(&self.key), &self.value) {
+            (Some(value), Some(expected)) => value == expected,
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+
+    fn query(&self) -> String {
+        match &self.value {
+            Some(value) => format!("{}={value}", self.key),
+            None => self.key.clone(),
+        }
+    }
+}
+
 pub struct DataSource {
     pub offset: usize,
     pub selected_index: usize,
     source: EntrySource,
     last_count: usize,
     all_sources: HashSet<Source>,
+    all_files: HashSet<Source>,
     seach_state: Option<SearchState>,
+    is_streaming: bool,
 }
 
 impl DataSource {
-    pub fn new(receiver: Receiver<LogEntry>) -> Self {
+    pub fn new(receiver: Receiver<LogEntry>, is_streaming: bool) -> Self {
         Self {
             offset: 0,
             selected_index: 0,
             last_count: 0,
             all_sources: HashSet::new(),
-            source: EntrySource::Plain(PlainSource::new(Buffer::new(receiver))),
+            all_files: HashSet::new(),
+            source: EntrySource::Plain(PlainSource::new(Buffer::new(receiver, is_streaming))),
             seach_state: None,
+            is_streaming,
         }
     }
 
@@ -55,7 +141,13 @@ impl DataSource {
             EntrySource::Plain(source) => source.buffer_len(),
             EntrySource::Filtered(source) => source.buffer_len(),
         };
-        let mut request_count = (self.offset + height * 2).saturating_sub(buffer_len);
+        let is_tailing =
+            self.is_streaming && buffer_len > 0 && self.selected_index + 1 == buffer_len;
+        let mut request_count = if is_tailing {
+            usize::MAX
+        } else {
+            (self.offset + height * 2).saturating_sub(buffer_len)
+        };
         while request_count > 0 {
             let entry = match &mut self.source {
                 EntrySource::Plain(source) => source.buffer.take_next(),
@@ -63,14 +155,19 @@ impl DataSource {
             };
             request_count = match entry {
                 Some(entry) => {
-                    if !self.all_sources.contains(&entry.source) {
-                        self.all_sources.insert(entry.source.clone());
-                    }
+                    register_sources(entry, &mut self.all_sources, &mut self.all_files);
                     request_count - 1
                 }
                 None => 0,
             }
         }
+        if is_tailing {
+            let buffer_len = match &self.source {
+                EntrySource::Plain(source) => source.buffer_len(),
+                EntrySource::Filtered(source) => source.buffer_len(),
+            };
+            self.selected_index = buffer_len - 1;
+        }
     }
 
     pub fn prepare_for_draw(&mut self, count: usize) {
@@ -93,7 +190,10 @@ impl DataSource {
             EntrySource::Plain(source) => source.buffer_len(),
             EntrySource::Filtered(source) => source.buffer_len(),
         };
-        self.selected_index = self.selected_index.saturating_add(1).min(buffer_len - 1);
+        self.selected_index = self
+            .selected_index
+            .saturating_add(1)
+            .min(buffer_len.saturating_sub(1));
         log::info!("Next log selected at index: {}", self.selected_index);
     }
 
@@ -131,23 +231,78 @@ impl DataSource {
 
     pub fn set_selected_sources(&mut self, sources: HashSet<u64>) {
         log::info!("Set new selected sources");
+        let is_all_sources = sources.is_empty() || sources.len() == self.all_sources.len();
+        let filter = Filter {
+            sources: (!is_all_sources).then_some(sources),
+            ..self.filter()
+        };
+        self.set_filter(filter);
+    }
+
+    pub fn set_selected_files(&mut self, files: HashSet<u64>) {
+        log::info!("Set new selected files");
+        let is_all_files = files.is_empty() || files.len() == self.all_files.len();
+        let filter = Filter {
+            files: (!is_all_files).then_some(files),
+            ..self.filter()
+        };
+        self.set_filter(filter);
+    }
+
+    pub fn set_min_level(&mut self, min_level: Option<Level>) {
+        log::info!("Set minimum level: {min_level:?}");
+        let filter = Filter {
+            min_level,
+            ..self.filter()
+        };
+        self.set_filter(filter);
+    }
+
+    pub fn min_level(&self) -> Option<Level> {
+        self.filter().min_level
+    }
+
+    pub fn set_field_filter(&mut self, query: &str) {
+        log::info!("Set field filter: {query}");
+        let filter = Filter {
+            fields: FieldFilter::parse(query),
+            ..self.filter()
+        };
+        self.set_filter(filter);
+    }
+
+    pub fn field_filter_query(&self) -> String {
+        self.filter()
+            .fields
+            .iter()
+            .map(FieldFilter::query)
+            .join(" ")
+    }
+
+    fn filter(&self) -> Filter {
+        match &self.source {
+            EntrySource::Plain(_) => Filter::default(),
+            EntrySource::Filtered(source) => source.filter.clone(),
+        }
+    }
+
+    fn set_filter(&mut self, filter: Filter) {
         self.offset = 0;
         self.selected_index = 0;
         self.seach_state = None;
 
-        let is_all_sources = sources.is_empty() || sources.len() == self.all_sources.len();
         match &mut self.source {
-            EntrySource::Plain(source) if !is_all_sources => {
+            EntrySource::Plain(source) if !filter.is_empty() => {
                 let buffer = std::mem::take(&mut source.buffer);
-                self.source = EntrySource::Filtered(FilteredSource::new(buffer, sources))
+                self.source = EntrySource::Filtered(FilteredSource::new(buffer, filter))
             }
-            EntrySource::Filtered(source) if is_all_sources => {
+            EntrySource::Filtered(source) if filter.is_empty() => {
                 let buffer = std::mem::take(&mut source.buffer);
                 self.source = EntrySource::Plain(PlainSource::new(buffer))
             }
-            EntrySource::Filtered(source) if source.selected_sources != sources => {
+            EntrySource::Filtered(source) if source.filter != filter => {
                 let buffer = std::mem::take(&mut source.buffer);
-                self.source = EntrySource::Filtered(FilteredSource::new(buffer, sources))
+                self.source = EntrySource::Filtered(FilteredSource::new(buffer, filter))
             }
             _ => {}
         }
@@ -190,6 +345,53 @@ impl DataSource {
         }
     }
 
+    /// Selects the entry holding the line, or the closest one before it
+    /// when the line was not parsed into an entry. Line numbers restart after
+    /// each rotation separator of the file, which starts the next generation.
+    /// Only entries the readers already sent are loaded, it never waits for more.
+    pub fn select_line(&mut self, file: &str, generation: usize, line_number: usize) {
+        log::info!("Select line {line_number} of {file}, generation {generation}");
+        let buffer = match &mut self.source {
+            EntrySource::Plain(source) => &mut source.buffer,
+            EntrySource::Filtered(source) => &mut source.buffer,
+        };
+        let mut target = None;
+        let mut entry_generation = 0;
+        for index in 0.. {
+            if index == buffer.len() {
+                match buffer.try_take_next() {
+                    Some(entry) => {
+                        register_sources(entry, &mut self.all_sources, &mut self.all_files)
+                    }
+                    None => break,
+                }
+            }
+            let entry = &buffer.inner()[index];
+            if entry.file.as_ref().map(|f| f.name.as_str()) != Some(file) {
+                continue;
+            }
+            if entry.is_separator {
+                entry_generation += 1;
+                continue;
+            }
+            match entry.line_number {
+                Some(entry_line) if (entry_generation, entry_line) <= (generation, line_number) => {
+                    target = Some(index)
+                }
+                Some(_) => break,
+                None => {}
+            }
+        }
+        let Some(index) = target else {
+            return;
+        };
+        self.selected_index = match &mut self.source {
+            EntrySource::Plain(_) => index,
+            EntrySource::Filtered(source) => source.position_of(index),
+        };
+        self.prepare_for_draw(self.last_count);
+    }
+
     pub fn active_message(&self) -> Option<&LogEntry> {
         match &self.source {
             EntrySource::Plain(source) => source.entry(self.selected_index),
@@ -224,13 +426,36 @@ impl DataSource {
     where
         F: FnMut((&Source, bool)),
     {
-        match &self.source {
-            EntrySource::Plain(_) => self.all_sources.iter().map(|s| (s, true)).for_each(f),
-            EntrySource::Filtered(source) => self
-                .all_sources
-                .iter()
-                .map(|s| (s, source.selected_sources.contains(&s.hash)))
-                .for_each(f),
+        let filter = self.filter();
+        self.all_sources
+            .iter()
+            .map(|s| (s, Filter::contains(&filter.sources, s)))
+            .for_each(f)
+    }
+
+    pub fn iterate_files<F>(&self, f: F)
+    where
+        F: FnMut((&Source, bool)),
+    {
+        let filter = self.filter();
+        self.all_files
+            .iter()
+            .map(|s| (s, Filter::contains(&filter.files, s)))
+            .for_each(f)
+    }
+}
+
+fn register_sources(
+    entry: &LogEntry,
+    all_sources: &mut HashSet<Source>,
+    all_files: &mut HashSet<Source>,
+) {
+    if !entry.is_separator && !all_sources.contains(&entry.source) {
+        all_sources.insert(entry.source.clone());
+    }
+    if let Some(file) = entry.file.as_ref() {
+        if !all_files.contains(file) {
+            all_files.insert(file.clone());
         }
     }
 }
@@ -279,7 +504,7 @@ impl PlainSource {
 }
 
 struct FilteredSource {
-    selected_sources: HashSet<u64>,
+    filter: Filter,
     indices: Vec<usize>,
     buffer: Buffer,
     range: Range<usize>,
@@ -288,9 +513,9 @@ struct FilteredSource {
 }
 
 impl FilteredSource {
-    fn new(buffer: Buffer, selected_sources: HashSet<u64>) -> Self {
+    fn new(buffer: Buffer, filter: Filter) -> Self {
         Self {
-            selected_sources,
+            filter,
             indices: Vec::new(),
             buffer,
             range: Range { start: 0, end: 0 },
@@ -350,6 +575,18 @@ impl FilteredSource {
     fn buffer_len(&self) -> usize {
         self.indices.len()
     }
+
+    /// Position of the last filtered entry at or before the buffer index.
+    fn position_of(&mut self, buffer_index: usize) -> usize {
+        while !self.is_end_reached && self.last_buffer_index < buffer_index {
+            if self.take_next().is_none() {
+                break;
+            }
+        }
+        self.indices
+            .partition_point(|index| *index <= buffer_index)
+            .saturating_sub(1)
+    }
 }
 
 impl SearchSourceBuffer for FilteredSource {
@@ -373,7 +610,7 @@ impl SearchSourceBuffer for FilteredSource {
         let mut entry_index = None;
         for (index, entry) in iter {
             self.last_buffer_index = index;
-            if self.selected_sources.contains(&entry.source.hash) {
+            if self.filter.matches(entry) {
                 self.indices.push(index);
                 entry_index = Some(index);
                 break;
@@ -393,7 +630,7 @@ impl SearchSourceBuffer for FilteredSource {
             match self.buffer.take_next() {
                 Some(entry) => {
                     self.last_buffer_index = len;
-                    if self.selected_sources.contains(&entry.source.hash) {
+                    if self.filter.matches(entry) {
                         self.indices.push(self.last_buffer_index);
                         entry_index = Some(self.last_buffer_index);
                         break;
@@ -114,7 +114,7 @@ impl SearchState {
         log::info!("Found next search index in cached data: {index:?}");
         let index = index.or_else(|| {
             while let Some(entry) = buffer.take_next() {
-                if entry.lower_case_message.contains(query) {
+                if entry.matches_query(query) {
                     let index = buffer.len() - 1;
                     log::info!("Found next search index in new data: {index}");
                     return Some(index);
@@ -137,7 +137,7 @@ impl SearchState {
     {
         iter.skip(start_index)
             .enumerate()
-            .find_map(|(index, entry)| entry.lower_case_message.contains(query).then_some(index))
+            .find_map(|(index, entry)| entry.matches_query(query).then_some(index))
             .map(|i| i + start_index)
     }
 }
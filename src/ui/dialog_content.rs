@@ -1,6 +1,6 @@
 use super::logs_panel::Styles;
-use crate::file_reader::log_entry::LogEntry;
-use cursive::{view::View, Vec2};
+use crate::file_reader::log_entry::{Level, LogEntry};
+use cursive::{theme::StyleType, view::View, Vec2};
 use itertools::Itertools;
 
 pub struct DialogContent {
@@ -19,6 +19,36 @@ impl DialogContent {
             styles: Styles::new(),
         }
     }
+
+    fn key_width(&self) -> usize {
+        self.message
+            .fields
+            .iter()
+            .map(|(key, _)| key.chars().count())
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn stack_trace_lines(&self) -> impl Iterator<Item = (usize, &str, StyleType)> {
+        self.message
+            .stack_trace
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                let line = line.trim();
+                let is_header =
+                    index == 0 || line.starts_with("Caused by:") || line.starts_with("Suppressed:");
+                if line.starts_with("at ") {
+                    (3, line, self.styles.msg_style)
+                } else if line.starts_with("...") {
+                    (3, line, self.styles.lines_style)
+                } else if is_header {
+                    (1, line, self.styles.level_style(Level::Error))
+                } else {
+                    (1, line, self.styles.msg_style)
+                }
+            })
+    }
 }
 
 impl View for DialogContent {
@@ -35,9 +65,9 @@ impl View for DialogContent {
         printer.with_style(self.styles.source_style, |p| {
             p.print((1, 1), &self.message.source.name);
         });
+        let width = printer.output_size.x;
+        let mut y_pos = 2;
         printer.with_style(self.styles.msg_style, |p| {
-            let width = printer.output_size.x;
-            let mut y_pos = 2;
             self.message.message.lines().for_each(|line| {
                 line.chars()
                     .chunks(width - 2)
@@ -49,6 +79,43 @@ impl View for DialogContent {
                     })
             });
         });
+        if !self.message.stack_trace.is_empty() {
+            y_pos += 1;
+            for (indent, line, style) in self.stack_trace_lines() {
+                printer.with_style(style, |p| {
+                    line.chars()
+                        .chunks(width.saturating_sub(indent + 1).max(1))
+                        .into_iter()
+                        .map(|chunk| chunk.collect::<String>())
+                        .for_each(|line| {
+                            p.print((indent, y_pos), &line);
+                            y_pos += 1;
+                        });
+                });
+            }
+        }
+        if self.message.fields.is_empty() {
+            return;
+        }
+        let key_width = self.key_width();
+        y_pos += 1;
+        for (key, value) in &self.message.fields {
+            printer.with_style(self.styles.source_style, |p| {
+                p.print((1, y_pos), key);
+            });
+            printer.with_style(self.styles.msg_style, |p| {
+                let value_width = width.saturating_sub(key_width + 3).max(1);
+                value
+                    .chars()
+                    .chunks(value_width)
+                    .into_iter()
+                    .map(|chunk| chunk.collect::<String>())
+                    .for_each(|line| {
+                        p.print((key_width + 2, y_pos), &line);
+                        y_pos += 1;
+                    });
+            });
+        }
     }
 
     fn required_size(&mut self, size: Vec2) -> Vec2 {
@@ -69,6 +136,35 @@ impl View for DialogContent {
                         };
                         Vec2::new(size.x.max(width), size.y + lines_count)
                     });
+                let size = if self.message.stack_trace.is_empty() {
+                    size
+                } else {
+                    self.stack_trace_lines().fold(
+                        size + Vec2::new(0, 1),
+                        |size, (indent, line, _)| {
+                            let line_width = max_width.saturating_sub(indent + 1).max(1);
+                            let length = line.chars().count();
+                            let lines_count = length.div_ceil(line_width).max(1);
+                            let width = (indent + length + 1).min(max_width);
+                            Vec2::new(size.x.max(width), size.y + lines_count)
+                        },
+                    )
+                };
+                let size = if self.message.fields.is_empty() {
+                    size
+                } else {
+                    let key_width = self.key_width();
+                    let value_width = max_width.saturating_sub(key_width + 3).max(1);
+                    self.message
+                        .fields
+                        .iter()
+                        .fold(size + Vec2::new(0, 1), |size, (_, value)| {
+                            let length = value.chars().count();
+                            let lines_count = length.div_ceil(value_width).max(1);
+                            let width = (key_width + length.min(value_width) + 3).min(max_width);
+                            Vec2::new(size.x.max(width), size.y + lines_count)
+                        })
+                };
                 self.required_size = Some(size);
                 size
             }
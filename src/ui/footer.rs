@@ -2,6 +2,7 @@ use super::{
     data_source::{PaginationState, SearchPaginationState},
     logs_panel::LogsPanel,
 };
+use crate::file_reader::diagnostics::Diagnostics;
 use cursive::{
     direction::Direction,
     event::{Event, EventResult, Key},
@@ -10,13 +11,19 @@ use cursive::{
     Printer, Vec2,
 };
 
+const HELP: &str = "esc: cancel, q: quit, s: sources, f: files, l: level, F: fields, \
+    u: unparsed, /: search";
+
 pub struct Footer {
     search_state: SearchState,
     search_query: String,
     cursor_position: usize,
     pagination_state: PaginationState,
+    format: String,
+    diagnostics: Diagnostics,
     info_color_style: ColorStyle,
     search_color_style: ColorStyle,
+    unparsed_color_style: ColorStyle,
 }
 
 enum SearchState {
@@ -26,7 +33,7 @@ enum SearchState {
 }
 
 impl Footer {
-    pub fn new() -> Self {
+    pub fn new(format: String, diagnostics: Diagnostics) -> Self {
         Self {
             search_state: SearchState::Disabled,
             search_query: String::new(),
@@ -35,8 +42,11 @@ impl Footer {
                 current: 1,
                 total: None,
             },
+            format: format!("[{format}]"),
+            diagnostics,
             info_color_style: ColorStyle::new(BaseColor::Cyan, PaletteColor::Background),
             search_color_style: ColorStyle::new(BaseColor::Green, PaletteColor::Background),
+            unparsed_color_style: ColorStyle::new(BaseColor::Red, PaletteColor::Background),
         }
     }
 
@@ -58,6 +68,14 @@ impl Footer {
         self.pagination_state = state;
     }
 
+    fn unparsed_msg(&self) -> String {
+        match self.diagnostics.count() {
+            0 => String::new(),
+            1 => "[1 unparsed line]".to_string(),
+            count => format!("[{count} unparsed lines]"),
+        }
+    }
+
     fn insert(&mut self, character: char) {
         if self.cursor_position >= self.search_query.len() {
             self.search_query.push(character);
@@ -106,10 +124,19 @@ impl View for Footer {
                 let mut start_pos = 1;
                 printer.with_color(self.search_color_style, |p| {
                     p.print((start_pos, 0), &page_msg);
-                    start_pos += page_msg.len();
+                    start_pos += page_msg.len() + 1;
+                    p.print((start_pos, 0), &self.format);
+                    start_pos += self.format.len();
                 });
+                let unparsed_msg = self.unparsed_msg();
+                if !unparsed_msg.is_empty() {
+                    printer.with_color(self.unparsed_color_style, |p| {
+                        p.print((start_pos + 1, 0), &unparsed_msg);
+                        start_pos += unparsed_msg.len() + 1;
+                    });
+                }
                 printer.with_color(self.info_color_style, |p| {
-                    p.print((start_pos + 1, 0), "esc: cancel, q: quit, s: filter sources, /: search");
+                    p.print((start_pos + 1, 0), HELP);
                 });
             }
             SearchState::Input => printer.with_color(self.search_color_style, |p| {
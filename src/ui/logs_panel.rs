@@ -1,26 +1,31 @@
 use super::data_source::PaginationState;
 use super::{data_source::SearchPaginationState, dialog_content::DialogContent, footer::Footer};
-use crate::file_reader::log_entry::LogEntry;
+use crate::file_reader::diagnostics::{Diagnostics, ParseFailure};
+use crate::file_reader::log_entry::{Level, LogEntry, Source};
 use crate::ui::data_source::DataSource;
 use crossbeam_channel::Receiver;
 use cursive::theme::{BaseColor, ColorStyle, PaletteColor, PaletteStyle, StyleType};
 use cursive::{
     direction::Direction,
     event::{Event, EventResult, Key},
-    view::{CannotFocus, View},
-    views::{Checkbox, ListView},
-    Printer, Vec2, XY,
+    view::{CannotFocus, Nameable, Resizable, Scrollable, View},
+    views::{Checkbox, EditView, LinearLayout, ListView, SelectView, TextView},
+    CbSink, Cursive, Printer, Vec2, XY,
 };
+use itertools::Itertools;
 use std::cell::RefCell;
 use std::collections::HashSet;
 use std::rc::Rc;
 
+const FIELD_FILTER_QUERY: &str = "field_filter_query";
+
 pub struct Styles {
     pub time_style: StyleType,
     pub source_style: StyleType,
     pub msg_style: StyleType,
     pub msg_style_hl: StyleType,
     pub lines_style: StyleType,
+    pub level_styles: [StyleType; 6],
 }
 
 impl Styles {
@@ -31,20 +36,45 @@ impl Styles {
             msg_style: ColorStyle::new(PaletteColor::Primary, PaletteColor::Background).into(),
             msg_style_hl: PaletteStyle::Highlight.into(),
             lines_style: ColorStyle::new(BaseColor::Cyan, PaletteColor::Background).into(),
+            level_styles: [
+                ColorStyle::new(BaseColor::White, PaletteColor::Background).into(),
+                ColorStyle::new(BaseColor::Cyan, PaletteColor::Background).into(),
+                ColorStyle::new(BaseColor::Green, PaletteColor::Background).into(),
+                ColorStyle::new(BaseColor::Yellow, PaletteColor::Background).into(),
+                ColorStyle::new(BaseColor::Red, PaletteColor::Background).into(),
+                ColorStyle::new(BaseColor::Magenta, PaletteColor::Background).into(),
+            ],
         }
     }
+
+    pub fn level_style(&self, level: Level) -> StyleType {
+        self.level_styles[level as usize]
+    }
 }
 
 pub struct LogsPanel {
     state: DataSource,
     styles: Styles,
+    is_streaming: bool,
+    cb_sink: CbSink,
+    diagnostics: Diagnostics,
+    last_pagination_state: Option<PaginationState>,
 }
 
 impl LogsPanel {
-    pub fn new(receiver: Receiver<LogEntry>) -> Self {
+    pub fn new(
+        receiver: Receiver<LogEntry>,
+        is_streaming: bool,
+        cb_sink: CbSink,
+        diagnostics: Diagnostics,
+    ) -> Self {
         Self {
-            state: DataSource::new(receiver),
+            state: DataSource::new(receiver, is_streaming),
             styles: Styles::new(),
+            is_streaming,
+            cb_sink,
+            diagnostics,
+            last_pagination_state: None,
         }
     }
 
@@ -66,6 +96,31 @@ impl LogsPanel {
         self.state.pagination_state()
     }
 
+    pub fn set_selected_files(&mut self, files: HashSet<u64>) -> PaginationState {
+        self.state.set_selected_files(files);
+        self.state.pagination_state()
+    }
+
+    pub fn set_field_filter(&mut self, query: &str) -> PaginationState {
+        self.state.set_field_filter(query);
+        self.state.pagination_state()
+    }
+
+    pub fn set_min_level(&mut self, level: Option<Level>) -> PaginationState {
+        self.state.set_min_level(level);
+        self.state.pagination_state()
+    }
+
+    pub fn select_line(
+        &mut self,
+        file: &str,
+        generation: usize,
+        line_number: usize,
+    ) -> PaginationState {
+        self.state.select_line(file, generation, line_number);
+        self.state.pagination_state()
+    }
+
     fn update_pagination_state(&self) -> EventResult {
         let pagination_state = self.state.pagination_state();
         EventResult::with_cb_once(move |c| {
@@ -75,6 +130,21 @@ impl LogsPanel {
         })
     }
 
+    fn sync_pagination_state(&mut self) {
+        let pagination_state = self.state.pagination_state();
+        if self.last_pagination_state.as_ref() == Some(&pagination_state) {
+            return;
+        }
+        self.last_pagination_state = Some(pagination_state.clone());
+        self.cb_sink
+            .send(Box::new(move |c: &mut Cursive| {
+                c.call_on_name(Footer::name(), |view: &mut Footer| {
+                    view.set_pagination_state(pagination_state);
+                });
+            }))
+            .unwrap();
+    }
+
     fn update_search_state(&self) -> EventResult {
         let state = self.state.search_pagination_state();
         EventResult::with_cb_once(|c| {
@@ -96,9 +166,134 @@ impl LogsPanel {
     }
 
     fn show_source_filter(&self) -> EventResult {
+        let (list_view, selected) = LogsPanel::checkbox_list(|f| self.state.iterate_sources(f));
+        LogsPanel::show_filter_dialog("Sources", list_view, move |view| {
+            let selected = selected.as_ref().replace(HashSet::new());
+            view.set_selected_sources(selected)
+        })
+    }
+
+    fn show_file_filter(&self) -> EventResult {
+        let (list_view, selected) = LogsPanel::checkbox_list(|f| self.state.iterate_files(f));
+        LogsPanel::show_filter_dialog("Files", list_view, move |view| {
+            let selected = selected.as_ref().replace(HashSet::new());
+            view.set_selected_files(selected)
+        })
+    }
+
+    fn show_level_filter(&self) -> EventResult {
+        let min_level = self.state.min_level();
+        let selected = Level::ALL
+            .iter()
+            .position(|level| Some(*level) == min_level)
+            .map(|index| index + 1)
+            .unwrap_or(0);
+        let select_view = Level::ALL
+            .into_iter()
+            .fold(SelectView::new().item("ALL", None), |view, level| {
+                view.item(level.name(), Some(level))
+            })
+            .selected(selected)
+            .on_submit(|c, level: &Option<Level>| {
+                let level = *level;
+                let pagination_state = c.call_on_name(Self::name(), |view: &mut LogsPanel| {
+                    view.set_min_level(level)
+                });
+                c.call_on_name(Footer::name(), |view: &mut Footer| {
+                    view.set_pagination_state(pagination_state.unwrap())
+                });
+                c.pop_layer();
+            });
+        EventResult::with_cb_once(|c| {
+            let dialog = cursive::views::Dialog::around(select_view)
+                .title("Minimum level")
+                .dismiss_button("Close");
+            c.add_layer(dialog);
+        })
+    }
+
+    fn show_field_filter(&self) -> EventResult {
+        let query = self.state.field_filter_query();
+        EventResult::with_cb_once(move |c| {
+            let submit = |c: &mut Cursive, query: &str| {
+                let pagination_state = c.call_on_name(Self::name(), |view: &mut LogsPanel| {
+                    view.set_field_filter(query)
+                });
+                c.call_on_name(Footer::name(), |view: &mut Footer| {
+                    view.set_pagination_state(pagination_state.unwrap())
+                });
+                c.pop_layer();
+            };
+            let edit_view = EditView::new()
+                .content(query)
+                .on_submit(submit)
+                .with_name(FIELD_FILTER_QUERY)
+                .min_width(40);
+            let content = LinearLayout::vertical()
+                .child(TextView::new("key=value or key, separated by spaces"))
+                .child(edit_view);
+            let dialog = cursive::views::Dialog::around(content)
+                .title("Fields")
+                .dismiss_button("Close")
+                .button("Submit", move |c| {
+                    let query = c
+                        .call_on_name(FIELD_FILTER_QUERY, |view: &mut EditView| view.get_content())
+                        .unwrap();
+                    submit(c, &query);
+                });
+            c.add_layer(dialog);
+        })
+    }
+
+    fn show_parse_failures(&self) -> EventResult {
+        let failures = self.diagnostics.failures();
+        let count = self.diagnostics.count();
+        EventResult::with_cb_once(move |c| {
+            if failures.is_empty() {
+                let dialog = cursive::views::Dialog::around(TextView::new("All lines are parsed"))
+                    .title("Unparsed lines")
+                    .dismiss_button("Close");
+                c.add_layer(dialog);
+                return;
+            }
+            let failures_len = failures.len();
+            let select_view = failures
+                .into_iter()
+                .fold(SelectView::new(), |view, failure| {
+                    let label = format!(
+                        "{}:{} {}: {}",
+                        failure.file, failure.line_number, failure.reason, failure.text
+                    );
+                    view.item(label, failure)
+                })
+                .on_submit(|c, failure: &ParseFailure| {
+                    let pagination_state = c.call_on_name(Self::name(), |view: &mut LogsPanel| {
+                        view.select_line(&failure.file, failure.generation, failure.line_number)
+                    });
+                    c.call_on_name(Footer::name(), |view: &mut Footer| {
+                        view.set_pagination_state(pagination_state.unwrap())
+                    });
+                    c.pop_layer();
+                });
+            let title = if count > failures_len {
+                format!("Unparsed lines, first {failures_len} of {count}")
+            } else {
+                "Unparsed lines".to_string()
+            };
+            let dialog = cursive::views::Dialog::around(select_view.scrollable())
+                .title(title)
+                .dismiss_button("Close");
+            c.add_layer(dialog);
+        })
+    }
+
+    fn checkbox_list<I>(iterate: I) -> (ListView, Rc<RefCell<HashSet<u64>>>)
+    where
+        I: FnOnce(&mut dyn FnMut((&Source, bool))),
+    {
         let mut list_view = ListView::new();
-        let selected = Rc::new(RefCell::new(std::collections::HashSet::<u64>::new()));
-        self.state.iterate_sources(|(source, is_selected)| {
+        let selected = Rc::new(RefCell::new(HashSet::<u64>::new()));
+        iterate(&mut |(source, is_selected): (&Source, bool)| {
             let hash = source.hash;
             if is_selected {
                 selected.as_ref().borrow_mut().insert(hash);
@@ -115,16 +310,21 @@ impl LogsPanel {
             checkbox.set_checked(is_selected);
             list_view.add_child(&source.name, checkbox);
         });
-        EventResult::with_cb_once(|c| {
+        (list_view, selected)
+    }
+
+    fn show_filter_dialog<F>(title: &'static str, list_view: ListView, submit: F) -> EventResult
+    where
+        F: Fn(&mut LogsPanel) -> PaginationState + 'static,
+    {
+        EventResult::with_cb_once(move |c| {
             let dialog = cursive::views::Dialog::new()
-                .title("Sources")
+                .title(title)
                 .dismiss_button("Close")
                 .content(list_view)
                 .button("Submit", move |c| {
-                    let pagination_state = c.call_on_name(Self::name(), |view: &mut LogsPanel| {
-                        let selected = selected.as_ref().replace(HashSet::new());
-                        view.set_selected_sources(selected)
-                    });
+                    let pagination_state =
+                        c.call_on_name(Self::name(), |view: &mut LogsPanel| submit(view));
                     c.call_on_name(Footer::name(), |view: &mut Footer| {
                         view.set_pagination_state(pagination_state.unwrap())
                     });
@@ -140,6 +340,9 @@ impl View for LogsPanel {
         let state = &mut self.state;
         state.load_logs(size.y);
         state.prepare_for_draw(size.y.saturating_sub(2));
+        if self.is_streaming {
+            self.sync_pagination_state();
+        }
     }
 
     fn draw(&self, printer: &Printer) {
@@ -151,30 +354,67 @@ impl View for LogsPanel {
 
         self.state.iterate_entries_to_draw(|(index, entry)| {
             let y_pos = index + 1;
-            let components_styles = if index == selected_index {
-                [styles.msg_style_hl; 3]
-            } else {
-                [styles.time_style, styles.source_style, styles.msg_style]
-            };
-            let lines = if entry.lines_count > 1 {
-                format!("[+{} lines]", entry.lines_count - 1)
-            } else {
-                String::new()
-            };
+            if entry.is_separator {
+                let style = if index == selected_index {
+                    styles.msg_style_hl
+                } else {
+                    styles.lines_style
+                };
+                let title = format!(" {} ", entry.message);
+                let start = width.saturating_sub(title.len()) / 2 + 1;
+                printer.with_style(style, |p| {
+                    p.print_hline((1, y_pos), width, "─");
+                    p.print((start, y_pos), &title);
+                });
+                return;
+            }
+            let is_selected = index == selected_index;
+            let lines_marker =
+                (entry.lines_count > 1).then(|| format!("[+{} lines]", entry.lines_count - 1));
+            let stack_trace_marker = (!entry.stack_trace.is_empty()).then(|| {
+                let exception = entry.exception_name().unwrap_or("stack trace");
+                format!("[{exception}: {} lines]", entry.stack_trace.len())
+            });
+            let encoding_marker = entry
+                .has_replacement_chars
+                .then(|| "[invalid bytes]".to_string());
+            let lines = [lines_marker, stack_trace_marker, encoding_marker]
+                .into_iter()
+                .flatten()
+                .join(" ");
+            let level = entry
+                .level
+                .map(|level| format!("{:<5}", level.name()))
+                .unwrap_or_default();
+            let level_style = entry
+                .level
+                .map(|level| styles.level_style(level))
+                .unwrap_or(styles.msg_style);
             let mut count_left = width.saturating_sub(lines.len() + 1);
             let mut start = 1;
             let components = [
-                &entry.date_time,
-                &entry.source.name,
-                &entry.one_line_message,
+                (&entry.date_time, styles.time_style),
+                (&level, level_style),
+                (&entry.source.name, styles.source_style),
+                (&entry.one_line_message, styles.msg_style),
             ];
             components
                 .into_iter()
-                .zip(components_styles.into_iter())
+                .filter(|(c, _)| !c.is_empty())
                 .for_each(|(c, style)| {
+                    let style = if is_selected {
+                        styles.msg_style_hl
+                    } else {
+                        style
+                    };
                     printer.with_style(style, |p| {
-                        let len = count_left.min(c.len());
-                        p.print((start, y_pos), &c[..len]);
+                        // Cut at a char boundary, invalid bytes are shown as U+FFFD.
+                        let text = match c.char_indices().nth(count_left) {
+                            Some((index, _)) => &c[..index],
+                            None => c.as_str(),
+                        };
+                        let len = text.chars().count();
+                        p.print((start, y_pos), text);
                         count_left = count_left.saturating_sub(len + 1);
                         if count_left > 0 {
                             p.print((start + len, y_pos), " ");
@@ -233,6 +473,10 @@ impl View for LogsPanel {
                 self.update_search_state()
             }
             Event::Char('s') => self.show_source_filter(),
+            Event::Char('f') => self.show_file_filter(),
+            Event::Char('l') => self.show_level_filter(),
+            Event::Char('F') => self.show_field_filter(),
+            Event::Char('u') => self.show_parse_failures(),
             Event::Key(Key::Enter) => self.show_active_message(),
             _ => EventResult::Ignored,
         }
@@ -1,3 +1,4 @@
+use crate::file_reader::diagnostics::Diagnostics;
 use crate::file_reader::log_entry::LogEntry;
 use crossbeam_channel::Receiver;
 use cursive::{
@@ -22,28 +23,48 @@ pub struct TermUI {
 #[allow(clippy::new_without_default)]
 impl TermUI {
     pub fn new() -> Self {
-        Self {
-            runnable: cursive::default(),
-        }
+        let runnable = CursiveRunnable::new(|| {
+            cursive::backends::curses::n::Backend::init_with_files("/dev/tty", "/dev/tty")
+        });
+        Self { runnable }
     }
 
     pub fn callback(&self) -> &CbSink {
         self.runnable.cb_sink()
     }
 
-    pub fn run(&mut self, receiver: Receiver<LogEntry>) {
+    pub fn run(
+        &mut self,
+        receiver: Receiver<LogEntry>,
+        format: String,
+        is_streaming: bool,
+        diagnostics: Diagnostics,
+    ) {
         self.runnable.set_theme(Theme::terminal_default());
         self.runnable.set_window_title("Tuna");
-        self.runnable
-            .add_fullscreen_layer(TermUI::build_ui(receiver));
+        let cb_sink = self.runnable.cb_sink().clone();
+        self.runnable.add_fullscreen_layer(TermUI::build_ui(
+            receiver,
+            format,
+            is_streaming,
+            cb_sink,
+            diagnostics,
+        ));
         self.runnable.add_global_callback('q', |c| c.quit());
         self.runnable.run();
     }
 
-    fn build_ui(receiver: Receiver<LogEntry>) -> impl view::View {
+    fn build_ui(
+        receiver: Receiver<LogEntry>,
+        format: String,
+        is_streaming: bool,
+        cb_sink: CbSink,
+        diagnostics: Diagnostics,
+    ) -> impl view::View {
+        let logs_panel = LogsPanel::new(receiver, is_streaming, cb_sink, diagnostics.clone());
         let view = views::LinearLayout::vertical()
-            .child(LogsPanel::new(receiver).with_name(LogsPanel::name()))
-            .child(Footer::new().with_name(Footer::name()))
+            .child(logs_panel.with_name(LogsPanel::name()))
+            .child(Footer::new(format, diagnostics).with_name(Footer::name()))
             .full_screen();
 
         OnEventView::new(view).on_pre_event_inner('/', |inner, _| {